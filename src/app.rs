@@ -1,18 +1,51 @@
-use std::collections::{HashMap, HashSet};
-use std::time::Instant;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant, SystemTime};
 
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
-use ratatui::widgets::ListState;
+use ratatui::layout::Rect;
+use ratatui::widgets::{ListState, Paragraph, Wrap};
 
-use crate::cache::CacheState;
+use crate::cache::{BranchState, CacheState, TestHistory};
 use crate::events::WatchEvent;
-use crate::model::{TestId, TestRegistry, TestStatus};
-use crate::repo::{package_for_path, PackageInfo};
-use crate::runner::{PackageRun, RunKind, RunSpec, RunnerCommand, RunnerEvent};
+use crate::go::{GoTestAction, GoTestEvent};
+use crate::model::{is_package_entry, TestCase, TestId, TestRegistry, TestStatus};
+use crate::repo::{cache_file, find_test_file, package_for_path, PackageInfo};
+use crate::runner::{
+    command_lines, PackageRun, RunKind, RunSpec, RunnerCommand, RunnerConfig, RunnerEvent,
+    CANCEL_MESSAGE,
+};
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `go test -timeout` used by the extended-timeout rerun: ten times go's
+/// default of 10m, enough to see where a hanging test is actually stuck.
+pub const EXTENDED_GO_TEST_TIMEOUT: Duration = Duration::from_secs(10 * 10 * 60);
+
+/// Two clicks on the same row within this window count as a double-click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Lines moved by PageUp/PageDown in the output pane.
+const DETAIL_PAGE: i32 = 10;
+
+/// A second `q` within this window quits while a run is in progress.
+const QUIT_CONFIRM: Duration = Duration::from_secs(2);
+
+/// Minimum time between automatic cursor moves in follow-failures mode.
+pub const FOLLOW_FAILURE_DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// Default for `App::watch_coalesce`.
+pub const WATCH_COALESCE: Duration = Duration::from_millis(1000);
+
+/// Errors kept for the error log (`E`); older ones are dropped.
+pub const ERROR_LOG_LIMIT: usize = 50;
+
+/// Results kept per test for the history sparkline.
+pub const HISTORY_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum RunMode {
     All,
     Failing,
@@ -28,12 +61,182 @@ pub struct RunState {
     pub packages_done: usize,
     pub running: bool,
     pub run_started_at: Option<Instant>,
+    pub run_finished_at: Option<Instant>,
+    /// First leaf test that failed in this run, for the jump-to key.
+    pub first_failure: Option<TestId>,
+    /// Packages go reported events for but no result yet, oldest first.
+    pub active_packages: Vec<String>,
+    /// Packages go reported a result for.
+    pub finished_packages: HashSet<String>,
+    /// Package whose failing test stopped this run under `--failfast`.
+    pub failed_fast: Option<String>,
+    /// Latest `--retries` attempt as (attempt, max).
+    pub retry: Option<(u32, u32)>,
+    /// Started by the file watcher rather than a key press.
+    pub from_watch: bool,
+    /// Stopped with `Esc`; results are left as they were.
+    pub cancelled: bool,
+}
+
+/// An entry of the error log.
+#[derive(Debug, Clone)]
+pub struct LoggedError {
+    pub at: Instant,
+    pub message: String,
+}
+
+/// One row of the package view: a package's tests tallied by status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageSummary {
+    pub package: String,
+    /// Failed if any test failed, running while the package or any of its
+    /// tests is in flight.
+    pub status: TestStatus,
+    pub counts: StatusCounts,
+    /// Wall-clock time of the package's last run, as reported by go.
+    pub duration: Option<Duration>,
+    /// Muted with `m`; listed last so it can be unmuted here.
+    pub muted: bool,
+}
+
+/// Internal counters shown in the `--debug` overlay.
+#[derive(Debug, Default, Clone)]
+pub struct DebugStats {
+    pub app_events_last_loop: usize,
+    pub runner_events_last_loop: usize,
+    pub pending_runner_events: usize,
+    pub package_cache_generated_at: Option<SystemTime>,
+}
+
+/// Visible tests tallied by status, for the top bar.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct StatusCounts {
+    pub passed: usize,
+    pub failed: usize,
+    pub running: usize,
+    pub skipped: usize,
+    pub unknown: usize,
+}
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum FuzzyCase {
+    #[default]
+    Smart,
+    Respect,
+    Ignore,
+}
+
+impl FuzzyCase {
+    pub fn next(self) -> Self {
+        match self {
+            FuzzyCase::Smart => FuzzyCase::Respect,
+            FuzzyCase::Respect => FuzzyCase::Ignore,
+            FuzzyCase::Ignore => FuzzyCase::Smart,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FuzzyCase::Smart => "smart case",
+            FuzzyCase::Respect => "case sensitive",
+            FuzzyCase::Ignore => "ignore case",
+        }
+    }
+}
+
+/// Narrows the All-mode list to a single status.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Failed,
+    Passed,
+    Running,
+}
+
+impl StatusFilter {
+    pub fn next(self) -> Self {
+        match self {
+            StatusFilter::All => StatusFilter::Failed,
+            StatusFilter::Failed => StatusFilter::Passed,
+            StatusFilter::Passed => StatusFilter::Running,
+            StatusFilter::Running => StatusFilter::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusFilter::All => "all",
+            StatusFilter::Failed => "failed only",
+            StatusFilter::Passed => "passed only",
+            StatusFilter::Running => "running only",
+        }
+    }
+
+    pub fn matches(self, status: TestStatus) -> bool {
+        match self {
+            StatusFilter::All => true,
+            StatusFilter::Failed => status == TestStatus::Failed,
+            StatusFilter::Passed => status == TestStatus::Passed,
+            StatusFilter::Running => status == TestStatus::Running,
+        }
+    }
+}
+
+/// Ordering of the All-mode list.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum SortOrder {
+    #[default]
+    Status,
+    Duration,
+}
+
+impl SortOrder {
+    pub fn next(self) -> Self {
+        match self {
+            SortOrder::Status => SortOrder::Duration,
+            SortOrder::Duration => SortOrder::Status,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortOrder::Status => "status",
+            SortOrder::Duration => "slowest first",
+        }
+    }
+}
+
+/// How the select-mode query is matched against tests.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FuzzyOptions {
+    pub case: FuzzyCase,
+    /// Match only against the test name, leaving the package out of the haystack.
+    pub name_only: bool,
+}
+
+/// What the preset name prompt does on Enter.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PresetAction {
+    Save,
+    Load,
+}
+
+/// Name being typed for saving (`S`) or loading (`G`) a selection preset.
+#[derive(Debug, Clone)]
+pub struct PresetPrompt {
+    pub action: PresetAction,
+    pub name: String,
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct SelectionState {
     pub query: String,
     pub filtered: Vec<TestId>,
+    /// Char positions in each filtered test's name that the query matched,
+    /// for highlighting.
+    pub positions: HashMap<TestId, Vec<usize>>,
+    pub fuzzy: FuzzyOptions,
 }
 
 pub struct App {
@@ -41,15 +244,156 @@ pub struct App {
     pub registry: TestRegistry,
     pub failing_set: HashSet<TestId>,
     pub selected_set: HashSet<TestId>,
+    /// Packages hidden from every view and left out of runs.
+    pub muted_packages: HashSet<String>,
+    /// Saved selections by name, shared by all branches.
+    pub presets: HashMap<String, Vec<TestId>>,
+    /// Last `HISTORY_LIMIT` results of each test, oldest first (`true` for a
+    /// pass), kept in the cache across sessions.
+    pub history: HashMap<TestId, Vec<bool>>,
+    /// Pass/fail results of the current run, added to `history` when it
+    /// finishes.
+    run_results: HashMap<TestId, bool>,
+    /// A full run finished uncancelled this session, so the registry knows
+    /// every test of the packages it ran and saved history can be pruned.
+    full_run_finished: bool,
+    pub preset_prompt: Option<PresetPrompt>,
+    /// Git branch the failing/selected sets belong to.
+    pub branch: Option<String>,
+    other_branches: HashMap<String, BranchState>,
     pub list_state: ListState,
+    /// Test under the cursor; `refresh_lists` follows it when the list re-sorts.
+    pub selected_test: Option<TestId>,
+    /// Show one row per package instead of one per test (`g`).
+    pub package_view: bool,
+    pub package_list_state: ListState,
+    /// Package opened from the package view; the test list shows only it.
+    pub package_focus: Option<String>,
+    /// Cursor position each mode had when it was last left.
+    mode_selection: HashMap<RunMode, TestId>,
+    /// Parent tests whose subtests are shown; the rest stay collapsed.
+    pub expanded: HashSet<TestId>,
+    /// Test list area and index of its first visible row, updated by the
+    /// renderer so clicks can be mapped to tests.
+    pub list_area: Cell<Rect>,
+    pub list_offset: Cell<usize>,
+    last_click: Option<(Instant, usize)>,
+    /// When `q` was pressed during a run, waiting for the confirming `q`.
+    quit_requested_at: Option<Instant>,
     pub detail_open: bool,
+    /// Up/Down scroll the output pane instead of moving the cursor.
+    pub detail_focused: bool,
+    /// First visible line of the output pane.
+    pub detail_scroll: u16,
+    /// Largest useful `detail_scroll`, updated by the renderer.
+    pub detail_scroll_max: Cell<u16>,
+    detail_scroll_test: Option<TestId>,
+    /// Keep the output pane scrolled to the end while the selected test is
+    /// running, like `tail -f`; toggled with `t`.
+    pub tail: bool,
+    /// Tailing was suspended by scrolling up, until the selection changes.
+    tail_paused: bool,
+    /// Output pane width inside the borders, updated by the renderer.
+    pub detail_width: Cell<u16>,
+    /// Show the output pane right of the list instead of below it.
+    pub detail_right: bool,
+    /// Flips how packages are named (`i`): full import paths in the test
+    /// list, which otherwise shortens them, and short names in the package
+    /// view, which otherwise shows them in full.
+    pub flip_package_paths: bool,
+    /// Shortest unambiguous name of each known package, rebuilt when a new
+    /// package shows up.
+    package_labels: HashMap<String, String>,
+    /// Output search query (`/`); matching lines are highlighted.
+    pub detail_search: String,
+    /// The search query is being typed.
+    pub detail_search_active: bool,
+    /// Lines of the current output matching `detail_search`.
+    pub detail_matches: Vec<usize>,
+    detail_match: Option<usize>,
+    pub raw_output: bool,
+    /// Show a line diff of the previous and current output (`d`).
+    pub detail_diff: bool,
     pub selection: SelectionState,
+    /// `listed_tests` and `visible_tests` as of the last `refresh_lists`, so
+    /// drawing doesn't filter and sort the list again.
+    listed: Vec<TestId>,
+    rows: Vec<TestId>,
     pub run_state: RunState,
     pub packages: Vec<PackageInfo>,
+    /// `go list` is still running in the background; `packages` is empty.
+    pub discovering_packages: bool,
+    /// Showing a `--replay`; keys that would start or cancel a run do
+    /// nothing.
+    pub replaying: bool,
     pub package_filter_active: bool,
+    /// `--packages` and `--exclude-packages` regexes, for the top bar.
+    pub package_include: Option<String>,
+    pub package_exclude: Option<String>,
+    /// Full runs use go's `all` pattern instead of `./...`.
+    pub test_all: bool,
+    /// Patterns of a full run: `./...`, or one per module of a workspace.
+    pub package_patterns: Vec<String>,
+    /// `--run` regex passed to `go test -run` for All-mode runs.
+    pub run_pattern: Option<String>,
     pub repo_root: std::path::PathBuf,
+    /// Rerun on file changes; toggled with `w`, and the main loop starts or
+    /// drops the watcher to match.
     pub watch_enabled: bool,
-    pub last_error: Option<String>,
+    /// Recent errors, oldest first; the top bar shows the newest.
+    pub errors: VecDeque<LoggedError>,
+    /// The error log overlay, toggled with `E`.
+    pub error_log_open: bool,
+    /// Notice for the top bar, cleared by the next key press.
+    pub info: Option<String>,
+    /// Opened on first copy and kept so X11 selections outlive the call.
+    clipboard: Option<arboard::Clipboard>,
+    /// File or directory to open in `$EDITOR`; taken by the main loop, which
+    /// suspends the TUI while the editor runs.
+    pub editor_request: Option<std::path::PathBuf>,
+    /// The configuration the runner was started with, for display.
+    pub runner_config: RunnerConfig,
+    /// Status filter for All mode, cycled with `f`.
+    pub status_filter: StatusFilter,
+    /// Sort order for All mode, cycled with `s`.
+    pub sort_order: SortOrder,
+    /// Rerun the failing set after every run until it passes (`L`).
+    pub loop_until_green: bool,
+    loop_rerun_due: bool,
+    /// Move the cursor to new failures as they stream in.
+    pub follow_failures: bool,
+    pending_focus: Option<TestId>,
+    last_auto_focus: Option<Instant>,
+    /// File changes this soon after a watch-triggered run started are
+    /// merged into one follow-up run instead of restarting it each time.
+    pub watch_coalesce: Duration,
+    /// Start of the last watch-triggered run, for coalescing file changes.
+    last_watch_run: Option<Instant>,
+    /// Changed packages (All mode) waiting for the coalesced watch rerun.
+    pending_watch: Option<HashSet<String>>,
+    /// The next run to start was sent by the watcher.
+    watch_run_requested: bool,
+    /// Notify when a watch-triggered run finishes (`--notify`).
+    pub notify: bool,
+    /// Desktop notification (summary, body) to send; taken by the main loop.
+    pub notification_request: Option<(String, String)>,
+    /// The last run sent to the runner, resent verbatim with `.`.
+    pub last_run: Option<RunSpec>,
+    pub help_open: bool,
+    /// First visible line of the help overlay.
+    pub help_scroll: u16,
+    /// Largest useful `help_scroll`, updated by the renderer.
+    pub help_scroll_max: Cell<u16>,
+    pub debug: bool,
+    pub debug_open: bool,
+    pub debug_stats: DebugStats,
+    pub unparsed_lines: usize,
+    /// Activity spinner frame, advanced on every tick while a run is active.
+    pub spinner_frame: usize,
+    /// Running tests without an event for this long are marked stuck.
+    pub stuck_after: Option<Duration>,
+    /// Running tests that look hung, recomputed on every tick.
+    pub stuck: HashSet<TestId>,
 }
 
 impl App {
@@ -62,8 +406,17 @@ impl App {
         watch_enabled: bool,
     ) -> Self {
         let mut registry = TestRegistry::default();
-        let failing_set: HashSet<TestId> = cache.failing.into_iter().collect();
-        let selected_set: HashSet<TestId> = cache.selected.into_iter().collect();
+        let mut branches = cache.branches;
+        let sets = match cache.branch.as_ref() {
+            Some(branch) => branches.remove(branch).unwrap_or_default(),
+            None => BranchState {
+                failing: cache.failing,
+                selected: cache.selected,
+            },
+        };
+        let failing_set: HashSet<TestId> = sets.failing.into_iter().collect();
+        let selected_set: HashSet<TestId> = sets.selected.into_iter().collect();
+        let muted_packages: HashSet<String> = cache.muted_packages.into_iter().collect();
 
         for test in failing_set.iter().chain(selected_set.iter()) {
             registry.ensure_test(test);
@@ -74,42 +427,480 @@ impl App {
             registry,
             failing_set,
             selected_set,
+            muted_packages,
+            presets: cache.presets,
+            history: cache
+                .history
+                .into_iter()
+                .map(|entry| (entry.test, entry.results))
+                .collect(),
+            run_results: HashMap::new(),
+            full_run_finished: false,
+            preset_prompt: None,
+            branch: cache.branch,
+            other_branches: branches,
             list_state: ListState::default(),
+            selected_test: None,
+            mode_selection: HashMap::new(),
+            package_view: false,
+            package_list_state: ListState::default(),
+            package_focus: None,
+            expanded: HashSet::new(),
+            list_area: Cell::new(Rect::default()),
+            list_offset: Cell::new(0),
+            last_click: None,
+            quit_requested_at: None,
             detail_open: false,
+            detail_focused: false,
+            detail_scroll: 0,
+            detail_scroll_max: Cell::new(u16::MAX),
+            detail_scroll_test: None,
+            tail: true,
+            tail_paused: false,
+            detail_width: Cell::new(0),
+            detail_right: false,
+            flip_package_paths: false,
+            package_labels: HashMap::new(),
+            detail_search: String::new(),
+            detail_search_active: false,
+            detail_matches: Vec::new(),
+            detail_match: None,
+            raw_output: false,
+            detail_diff: false,
             selection: SelectionState::default(),
+            listed: Vec::new(),
+            rows: Vec::new(),
             run_state: RunState::default(),
             packages,
+            discovering_packages: false,
+            replaying: false,
             package_filter_active,
+            package_include: None,
+            package_exclude: None,
+            test_all: false,
+            package_patterns: vec!["./...".to_string()],
+            run_pattern: None,
             repo_root,
             watch_enabled,
-            last_error: None,
+            errors: VecDeque::new(),
+            error_log_open: false,
+            info: None,
+            clipboard: None,
+            editor_request: None,
+            runner_config: RunnerConfig::default(),
+            status_filter: StatusFilter::All,
+            sort_order: SortOrder::Status,
+            loop_until_green: false,
+            loop_rerun_due: false,
+            follow_failures: false,
+            pending_focus: None,
+            last_auto_focus: None,
+            watch_coalesce: WATCH_COALESCE,
+            last_watch_run: None,
+            pending_watch: None,
+            watch_run_requested: false,
+            notify: false,
+            notification_request: None,
+            last_run: None,
+            help_open: false,
+            help_scroll: 0,
+            help_scroll_max: Cell::new(u16::MAX),
+            debug: false,
+            debug_open: false,
+            debug_stats: DebugStats::default(),
+            unparsed_lines: 0,
+            spinner_frame: 0,
+            stuck_after: None,
+            stuck: HashSet::new(),
         };
 
         app.refresh_lists();
         app
     }
 
+    /// Whether `test` no longer exists: a full run covered its package but
+    /// did not report it.
+    fn removed_test(&self, test: &TestId) -> bool {
+        self.full_run_finished
+            && self.registry.case(test).is_none()
+            && self.registry.package_duration(&test.package).is_some()
+    }
+
     pub fn cache_state(&self) -> CacheState {
-        CacheState {
+        let sets = BranchState {
             failing: self.failing_set.iter().cloned().collect(),
             selected: self.selected_set.iter().cloned().collect(),
-            package_cache: None,
+        };
+        let mut state = CacheState {
+            branches: self.other_branches.clone(),
+            branch: self.branch.clone(),
+            muted_packages: self.muted_packages.iter().cloned().collect(),
+            presets: self.presets.clone(),
+            history: self
+                .history
+                .iter()
+                .filter(|(test, _)| !self.removed_test(test))
+                .map(|(test, results)| TestHistory {
+                    test: test.clone(),
+                    results: results.clone(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        match self.branch.as_ref() {
+            Some(branch) => {
+                state.branches.insert(branch.clone(), sets);
+            }
+            None => {
+                state.failing = sets.failing;
+                state.selected = sets.selected;
+            }
         }
+        state
     }
 
+    /// Rows of the test list. Outside select mode subtests are grouped under
+    /// their parent tests, which stay collapsed until expanded.
     pub fn visible_tests(&self) -> Vec<TestId> {
-        match self.mode {
+        self.rows_of(self.listed_tests())
+    }
+
+    fn rows_of(&self, tests: Vec<TestId>) -> Vec<TestId> {
+        if self.mode == RunMode::Selecting {
+            return tests;
+        }
+        self.tree_rows(tests)
+    }
+
+    /// The rows drawn, as of the last refresh.
+    pub fn rows(&self) -> &[TestId] {
+        &self.rows
+    }
+
+    /// The row under the cursor, as of the last refresh.
+    pub fn selected_row(&self) -> Option<&TestId> {
+        self.rows.get(self.list_state.selected()?)
+    }
+
+    /// Leaf tests the current mode lists, before grouping into a tree.
+    fn listed_tests(&self) -> Vec<TestId> {
+        let mut tests = match self.mode {
             RunMode::All => self.sorted_all_tests(),
             RunMode::Failing => self.sorted_from_set(&self.failing_set),
             RunMode::Selected => self.sorted_from_set(&self.selected_set),
             RunMode::Selecting => self.selection.filtered.clone(),
+        };
+        if !self.muted_packages.is_empty() {
+            tests.retain(|test| !self.muted_packages.contains(&test.package));
+        }
+        if let Some(package) = self.package_focus.as_ref() {
+            tests.retain(|test| test.package == *package);
+        }
+        tests
+    }
+
+    /// Rows of the package view: every package with tests, failures first
+    /// and muted packages last.
+    pub fn package_summaries(&self) -> Vec<PackageSummary> {
+        let mut summaries: Vec<PackageSummary> = Vec::new();
+        let mut index_of: HashMap<String, usize> = HashMap::new();
+        for test in self.registry.leaf_tests() {
+            let index = *index_of.entry(test.package.clone()).or_insert_with(|| {
+                summaries.push(PackageSummary {
+                    package: test.package.clone(),
+                    status: TestStatus::Unknown,
+                    counts: StatusCounts::default(),
+                    duration: self.registry.package_duration(&test.package),
+                    muted: self.muted_packages.contains(&test.package),
+                });
+                summaries.len() - 1
+            });
+            let status = self
+                .registry
+                .case(&test)
+                .map(|case| case.status)
+                .unwrap_or(TestStatus::Unknown);
+            let counts = &mut summaries[index].counts;
+            match status {
+                TestStatus::Passed => counts.passed += 1,
+                TestStatus::Failed => counts.failed += 1,
+                TestStatus::Running => counts.running += 1,
+                TestStatus::Skipped => counts.skipped += 1,
+                TestStatus::Unknown => counts.unknown += 1,
+            }
+        }
+        for summary in &mut summaries {
+            let counts = summary.counts;
+            let active = self.run_state.active_packages.contains(&summary.package);
+            summary.status = if counts.failed > 0 {
+                TestStatus::Failed
+            } else if counts.running > 0 || active {
+                TestStatus::Running
+            } else if counts.passed > 0 {
+                TestStatus::Passed
+            } else if counts.skipped > 0 {
+                TestStatus::Skipped
+            } else {
+                TestStatus::Unknown
+            };
+        }
+        let rank = |summary: &PackageSummary| match summary.status {
+            _ if summary.muted => 3,
+            TestStatus::Failed => 0,
+            TestStatus::Running => 1,
+            _ => 2,
+        };
+        summaries.sort_by(|a, b| {
+            rank(a)
+                .cmp(&rank(b))
+                .then_with(|| a.package.cmp(&b.package))
+        });
+        summaries
+    }
+
+    /// Adds `message` to the error log, dropping the oldest entry when full.
+    pub fn report_error(&mut self, message: impl Into<String>) {
+        if self.errors.len() == ERROR_LOG_LIMIT {
+            self.errors.pop_front();
+        }
+        self.errors.push_back(LoggedError {
+            at: Instant::now(),
+            message: message.into(),
+        });
+    }
+
+    /// The most recent error, shown in the top bar.
+    pub fn last_error(&self) -> Option<&str> {
+        self.errors.back().map(|error| error.message.as_str())
+    }
+
+    /// Listed tests tallied by their current status.
+    pub fn status_counts(&self) -> StatusCounts {
+        let mut counts = StatusCounts::default();
+        for test in &self.listed {
+            let status = self
+                .registry
+                .case(test)
+                .map(|case| case.status)
+                .unwrap_or(TestStatus::Unknown);
+            match status {
+                TestStatus::Passed => counts.passed += 1,
+                TestStatus::Failed => counts.failed += 1,
+                TestStatus::Running => counts.running += 1,
+                TestStatus::Skipped => counts.skipped += 1,
+                TestStatus::Unknown => counts.unknown += 1,
+            }
+        }
+        counts
+    }
+
+    /// Parent tests of `id` that the registry knows about, outermost first.
+    pub fn ancestors(&self, id: &TestId) -> Vec<TestId> {
+        id.name
+            .match_indices('/')
+            .map(|(index, _)| TestId {
+                package: id.package.clone(),
+                name: id.name[..index].to_string(),
+            })
+            .filter(|parent| self.registry.is_parent(parent))
+            .collect()
+    }
+
+    /// Status shown for a list row. A parent test summarizes its subtests:
+    /// failed if any failed, otherwise running if any is still running.
+    /// `parents` comes from `parent_statuses`, computed once per pass over
+    /// the list.
+    pub fn row_status(&self, id: &TestId, parents: &HashMap<TestId, TestStatus>) -> TestStatus {
+        parents.get(id).copied().unwrap_or_else(|| {
+            self.registry
+                .case(id)
+                .map(|case| case.status)
+                .unwrap_or(TestStatus::Unknown)
+        })
+    }
+
+    /// The summarized status of every parent test with known subtests, in a
+    /// single pass over the leaves.
+    pub fn parent_statuses(&self) -> HashMap<TestId, TestStatus> {
+        let mut ranked: HashMap<TestId, (usize, TestStatus)> = HashMap::new();
+        for test in self.registry.leaf_tests() {
+            let Some(case) = self.registry.case(&test) else {
+                continue;
+            };
+            let rank = self.status_rank(&test);
+            for parent in self.ancestors(&test) {
+                let entry = ranked.entry(parent).or_insert((rank, case.status));
+                if rank < entry.0 {
+                    *entry = (rank, case.status);
+                }
+            }
         }
+        ranked
+            .into_iter()
+            .map(|(id, (_, status))| (id, status))
+            .collect()
     }
 
     pub fn current_test(&self) -> Option<TestId> {
-        let list = self.visible_tests();
-        let index = self.list_state.selected()?;
-        list.get(index).cloned()
+        self.selected_row().cloned()
+    }
+
+    /// Text for the output pane: panic and data race banners, the test's own
+    /// output and the output of any panicked parents. Uses raw output in raw
+    /// mode.
+    pub fn detail_text(&self, test: &TestId) -> Option<String> {
+        let case = self.registry.case(test)?;
+        if self.detail_diff {
+            return Some(output_diff(&case.prev_output, &case.output));
+        }
+        let output_of = |case: &TestCase| {
+            if self.raw_output {
+                case.raw_output.clone()
+            } else {
+                case.output.clone()
+            }
+        };
+        let parents = self.registry.panicked_parents(test);
+        let mut output = String::new();
+        if case.panic || !parents.is_empty() {
+            output.push_str("PANIC DETECTED\n");
+        }
+        if case.race {
+            output.push_str("DATA RACE DETECTED\n");
+        }
+        output.push_str(&output_of(case));
+        for (parent, parent_case) in parents {
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str(&format!("--- output from parent {} ---\n", parent.name));
+            output.push_str(&output_of(parent_case));
+        }
+        for (subtest, subtest_case) in self.subtest_outputs(test) {
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str(&format!("--- output from {} ---\n", subtest.name));
+            output.push_str(&output_of(subtest_case));
+        }
+        Some(output)
+    }
+
+    /// Subtests of a parent test that printed something, in the order their
+    /// output was emitted, so parallel subtests read the same on every view.
+    fn subtest_outputs(&self, parent: &TestId) -> Vec<(TestId, &TestCase)> {
+        if !self.registry.is_parent(parent) {
+            return Vec::new();
+        }
+        let mut subtests: Vec<(TestId, &TestCase)> = self
+            .registry
+            .leaf_tests()
+            .into_iter()
+            .filter(|test| test != parent && is_within(test, parent))
+            .filter_map(|test| {
+                let case = self.registry.case(&test)?;
+                (!case.output.is_empty()).then_some((test, case))
+            })
+            .collect();
+        subtests.sort_by_key(|(test, case)| (case.output_seq, self.registry.order_index(test)));
+        subtests
+    }
+
+    fn copy_current_output(&mut self) {
+        let Some(test) = self.current_test() else {
+            self.report_error("no test selected, nothing to copy");
+            return;
+        };
+        let text = self.detail_text(&test).unwrap_or_default();
+        self.copy_to_clipboard(text);
+    }
+
+    /// The shell command that reruns the selected test, one line per
+    /// `go test` invocation, exactly as the runner would spawn it.
+    pub fn current_run_command(&self) -> Option<String> {
+        let test = self.current_test()?;
+        let tests = HashSet::from([test]);
+        let spec = self.spec_for_tests(RunKind::Single, &tests, None)?;
+        Some(command_lines(&self.runner_config, &spec).join("\n"))
+    }
+
+    fn copy_run_command(&mut self) {
+        match self.current_run_command() {
+            Some(command) => self.copy_to_clipboard(command),
+            None => self.report_error("no test selected, nothing to copy"),
+        }
+    }
+
+    fn copy_to_clipboard(&mut self, text: String) {
+        if self.clipboard.is_none() {
+            match arboard::Clipboard::new() {
+                Ok(clipboard) => self.clipboard = Some(clipboard),
+                Err(err) => {
+                    self.report_error(format!("clipboard unavailable: {}", err));
+                    return;
+                }
+            }
+        }
+        if let Some(clipboard) = self.clipboard.as_mut() {
+            if let Err(err) = clipboard.set_text(text) {
+                self.report_error(format!("copy failed: {}", err));
+            }
+        }
+    }
+
+    /// Asks for the source of the selected test to be opened in `$EDITOR`,
+    /// falling back to its package directory when the function isn't found.
+    fn open_current_in_editor(&mut self) {
+        let Some(test) = self.current_test() else {
+            self.report_error("no test selected, nothing to open");
+            return;
+        };
+        let Some(package) = self
+            .packages
+            .iter()
+            .find(|package| package.import_path == test.package)
+        else {
+            self.report_error(format!("no directory known for {}", test.package));
+            return;
+        };
+        self.editor_request =
+            Some(find_test_file(&package.dir, &test.name).unwrap_or_else(|| package.dir.clone()));
+    }
+
+    pub fn tick(&mut self) {
+        if self.run_state.running {
+            self.spinner_frame = self.spinner_frame.wrapping_add(1);
+        } else {
+            self.spinner_frame = 0;
+        }
+        self.update_stuck();
+        // A failure held back by the debounce lands even if no more events
+        // arrive.
+        self.apply_pending_focus();
+    }
+
+    /// Collects running tests that have gone `stuck_after` without output or
+    /// a result, e.g. a test waiting forever on a channel.
+    fn update_stuck(&mut self) {
+        self.stuck.clear();
+        let Some(limit) = self.stuck_after.filter(|_| self.run_state.running) else {
+            return;
+        };
+        for (test, case) in self.registry.running_tests() {
+            let last_seen = case.last_update.or(case.started_at);
+            let silent = last_seen.is_some_and(|seen| seen.elapsed() >= limit);
+            // A paused parallel test is waiting for its turn.
+            if silent && !case.paused {
+                self.stuck.insert(test.clone());
+            }
+        }
+    }
+
+    /// Wall-clock time the last run took, once it has finished.
+    pub fn run_duration(&self) -> Option<Duration> {
+        let run = &self.run_state;
+        let finished = run.run_finished_at.filter(|_| !run.running)?;
+        Some(finished.duration_since(run.run_started_at?))
     }
 
     pub fn test_progress(&self) -> (usize, usize) {
@@ -125,7 +916,10 @@ impl App {
             .filter(|case| case.last_update.map(|ts| ts >= start).unwrap_or(false))
             .filter(|case| {
                 total += 1;
-                matches!(case.status, TestStatus::Passed | TestStatus::Failed)
+                matches!(
+                    case.status,
+                    TestStatus::Passed | TestStatus::Failed | TestStatus::Skipped
+                )
             })
             .count();
         (done, total)
@@ -141,6 +935,9 @@ impl App {
     {
         let mut refresh_selection = false;
         let mut refresh_failing = false;
+        let mut run_finished = false;
+        let mut notification = None;
+        let was_tailing = self.tailing();
 
         for event in events {
             match event {
@@ -152,11 +949,20 @@ impl App {
                     self.run_state = RunState {
                         run_id: Some(run_id),
                         kind: Some(kind),
-                        packages_total: packages,
+                        packages_total: self.expected_packages().unwrap_or(packages),
                         packages_done: 0,
                         running: true,
                         run_started_at: Some(Instant::now()),
+                        run_finished_at: None,
+                        first_failure: None,
+                        active_packages: Vec::new(),
+                        finished_packages: HashSet::new(),
+                        failed_fast: None,
+                        retry: None,
+                        from_watch: std::mem::take(&mut self.watch_run_requested),
+                        cancelled: false,
                     };
+                    self.run_results.clear();
                 }
                 RunnerEvent::PackageFinished {
                     run_id,
@@ -166,7 +972,6 @@ impl App {
                     if !self.is_current_run(run_id) {
                         continue;
                     }
-                    self.run_state.packages_done = self.run_state.packages_done.saturating_add(1);
                     if let Some(start) = self.run_state.run_started_at {
                         let status = if success {
                             TestStatus::Passed
@@ -182,6 +987,18 @@ impl App {
                         continue;
                     }
                     self.run_state.running = false;
+                    self.run_state.run_finished_at = Some(Instant::now());
+                    self.run_state.active_packages.clear();
+                    // Killed jobs may be labelled `./...` rather than by
+                    // package, so their tests were never finalized.
+                    if self.run_state.cancelled || self.run_state.failed_fast.is_some() {
+                        self.registry.abandon_running();
+                    }
+                    run_finished = true;
+                    if self.notify && self.run_state.from_watch {
+                        notification = Some(self.run_notification());
+                    }
+                    self.record_history(kind);
                     if kind == RunKind::All {
                         refresh_failing = true;
                     }
@@ -191,6 +1008,25 @@ impl App {
                         continue;
                     }
                     self.registry.apply_event(&event);
+                    self.track_package(&event);
+                    if let (Some(name), GoTestAction::Pass | GoTestAction::Fail) =
+                        (event.test.as_ref(), &event.action)
+                    {
+                        let id = TestId {
+                            package: event.package.clone(),
+                            name: name.clone(),
+                        };
+                        self.run_results
+                            .insert(id, event.action == GoTestAction::Pass);
+                    }
+                    if let Some(id) = self.failed_leaf(&event) {
+                        if self.run_state.first_failure.is_none() {
+                            self.run_state.first_failure = Some(id.clone());
+                        }
+                        if self.follow_failures {
+                            self.pending_focus = Some(id);
+                        }
+                    }
                     if self.mode == RunMode::Selecting {
                         refresh_selection = true;
                     }
@@ -199,23 +1035,233 @@ impl App {
                     if !self.is_current_run(run_id) {
                         continue;
                     }
-                    self.last_error = Some(message);
+                    // The top bar already says so.
+                    if self.run_state.cancelled && message == CANCEL_MESSAGE {
+                        continue;
+                    }
+                    self.report_error(message);
+                }
+                RunnerEvent::UnparsedLine { run_id, .. } => {
+                    if !self.is_current_run(run_id) {
+                        continue;
+                    }
+                    self.unparsed_lines = self.unparsed_lines.saturating_add(1);
+                }
+                RunnerEvent::Retry {
+                    run_id,
+                    package,
+                    tests,
+                    attempt,
+                    max,
+                } => {
+                    if !self.is_current_run(run_id) {
+                        continue;
+                    }
+                    for name in tests {
+                        let id = TestId {
+                            package: package.clone(),
+                            name,
+                        };
+                        self.registry.record_failed_attempt(&id);
+                    }
+                    self.run_state.retry = Some((attempt, max));
                 }
-                RunnerEvent::PackageStarted { run_id, .. } => {
+                RunnerEvent::FailFast { run_id, package } => {
                     if !self.is_current_run(run_id) {
                         continue;
                     }
+                    self.run_state.failed_fast = Some(package);
                 }
+                // Jobs may span many packages (`./...`); progress comes from
+                // go's own package events instead.
+                RunnerEvent::PackageStarted { .. } => {}
             }
         }
 
         if refresh_failing {
             self.update_failing_set();
         }
+        // A `(build)` or teardown entry is gone once its package is fine.
+        let registry = &self.registry;
+        self.failing_set
+            .retain(|id| !is_package_entry(&id.name) || registry.case(id).is_some());
+        if run_finished && self.loop_until_green {
+            if self.has_failures_in_failing_set() {
+                self.loop_rerun_due = true;
+            } else {
+                self.loop_until_green = false;
+            }
+        }
         if refresh_selection {
             self.refresh_selection_filter();
         }
         self.refresh_lists();
+        self.apply_pending_focus();
+        if notification.is_some() {
+            self.notification_request = notification;
+        }
+        if was_tailing && !self.tailing() {
+            // Stay at the end of the output once the test finishes.
+            self.detail_scroll = self.detail_scroll_max.get();
+        }
+    }
+
+    /// Packages the last run sent covers: the listed ones, plus every
+    /// discovered package for a pattern such as `./...`. `None` if no run
+    /// was sent, e.g. during a replay.
+    fn expected_packages(&self) -> Option<usize> {
+        let spec = self.last_run.as_ref()?;
+        let packages = spec.packages.iter().flat_map(|job| job.packages.iter());
+        let (patterns, listed): (Vec<&String>, Vec<&String>) =
+            packages.partition(|package| package.contains("...") || *package == "all");
+        let discovered = if patterns.is_empty() {
+            0
+        } else {
+            self.packages.len()
+        };
+        Some(listed.len() + discovered)
+    }
+
+    /// Tracks the packages in flight and done from go's package events: the
+    /// first event of a package starts it, its package-level result ends it.
+    fn track_package(&mut self, event: &GoTestEvent) {
+        let run = &mut self.run_state;
+        if event.package.is_empty() || run.finished_packages.contains(&event.package) {
+            return;
+        }
+        let finished = event.test.is_none()
+            && matches!(
+                event.action,
+                GoTestAction::Pass | GoTestAction::Fail | GoTestAction::Skip
+            );
+        if finished {
+            run.active_packages
+                .retain(|active| *active != event.package);
+            run.finished_packages.insert(event.package.clone());
+            run.packages_done = run.finished_packages.len();
+        } else if !run.active_packages.contains(&event.package) {
+            run.active_packages.push(event.package.clone());
+        }
+        let seen = run.active_packages.len() + run.finished_packages.len();
+        run.packages_total = run.packages_total.max(seen);
+    }
+
+    /// Appends the finished run's results to each test's history. Cancelled
+    /// runs are partial and cached results only repeat an earlier run, so
+    /// neither is recorded.
+    fn record_history(&mut self, kind: RunKind) {
+        let run_results = std::mem::take(&mut self.run_results);
+        if self.run_state.cancelled {
+            return;
+        }
+        // A `--run` pattern leaves out tests that still exist.
+        if kind == RunKind::All
+            && self.run_state.failed_fast.is_none()
+            && self.run_pattern.is_none()
+        {
+            self.full_run_finished = true;
+        }
+        for (test, passed) in run_results {
+            if self.registry.case(&test).is_some_and(|case| case.cached) {
+                continue;
+            }
+            let results = self.history.entry(test).or_default();
+            results.push(passed);
+            if results.len() > HISTORY_LIMIT {
+                results.remove(0);
+            }
+        }
+    }
+
+    /// Summary and body of the notification for a finished run, counting
+    /// the tests that run reported on, not whatever the view lists.
+    fn run_notification(&self) -> (String, String) {
+        let (mut passed, mut failed) = (0, 0);
+        for (test, &pass) in &self.run_results {
+            if self.registry.is_parent(test) {
+                continue;
+            }
+            if pass {
+                passed += 1;
+            } else {
+                failed += 1;
+            }
+        }
+        let summary = if failed > 0 {
+            format!("gest: {} failed", failed)
+        } else {
+            "gest: tests passed".to_string()
+        };
+        let body = format!("{} passed, {} failed", passed, failed);
+        (summary, body)
+    }
+
+    /// Starts work that runner events scheduled but could not send
+    /// themselves, i.e. the next iteration of the loop-until-green mode.
+    pub fn run_due_reruns(&mut self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) {
+        if std::mem::take(&mut self.loop_rerun_due) && self.loop_until_green {
+            self.run_failing(runner_tx);
+        }
+        if self.pending_watch.is_some() && self.watch_rerun_due() {
+            self.start_watch_run(runner_tx);
+        }
+    }
+
+    fn watch_rerun_due(&self) -> bool {
+        self.last_watch_run
+            .is_none_or(|started| started.elapsed() >= self.watch_coalesce)
+    }
+
+    /// Runs what the pending file changes affect: the changed packages in
+    /// All mode, otherwise the failing or selected tests.
+    fn start_watch_run(&mut self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) {
+        let Some(packages) = self.pending_watch.take() else {
+            return;
+        };
+        self.last_watch_run = Some(Instant::now());
+        self.watch_run_requested = match self.mode {
+            RunMode::All => {
+                if packages.is_empty() {
+                    return;
+                }
+                self.cancel_current_run(runner_tx);
+                let mut packages: Vec<String> = packages.into_iter().collect();
+                packages.sort();
+                let spec = RunSpec {
+                    kind: RunKind::All,
+                    packages: vec![PackageRun {
+                        packages,
+                        tests: None,
+                    }],
+                    no_test_cache_override: None,
+                    timeout: self.runner_config.run_timeout,
+                    go_test_timeout: None,
+                    run_pattern: self.run_pattern.clone(),
+                };
+                self.send_run(spec, runner_tx);
+                true
+            }
+            RunMode::Failing => self.run_failing(runner_tx),
+            RunMode::Selected | RunMode::Selecting => self.run_selected(runner_tx),
+        };
+    }
+
+    fn has_failures_in_failing_set(&self) -> bool {
+        self.failing_set.iter().any(|test| {
+            self.registry
+                .case(test)
+                .is_some_and(|case| case.status == TestStatus::Failed)
+        })
+    }
+
+    fn toggle_loop_until_green(&mut self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) {
+        if self.loop_until_green {
+            self.loop_until_green = false;
+        } else if self.has_failures_in_failing_set() {
+            self.loop_until_green = self.run_failing(runner_tx);
+        } else {
+            self.report_error("nothing is failing, no loop started");
+        }
     }
 
     pub fn handle_watch_event(
@@ -228,37 +1274,31 @@ impl App {
                 if !self.watch_enabled {
                     return;
                 }
+                let mut packages = HashSet::new();
                 if self.mode == RunMode::All {
-                    let mut packages = HashSet::new();
                     for path in paths {
                         if !is_go_file(&path) {
                             continue;
                         }
                         if let Some(package) = package_for_path(&self.packages, &path) {
-                            packages.insert(package.import_path.clone());
+                            if !self.muted_packages.contains(&package.import_path) {
+                                packages.insert(package.import_path.clone());
+                            }
                         }
                     }
-                    if !packages.is_empty() {
-                        self.cancel_current_run(runner_tx);
-                        let spec = RunSpec {
-                            kind: RunKind::All,
-                            packages: vec![PackageRun {
-                                packages: packages.into_iter().collect(),
-                                tests: None,
-                            }],
-                            no_test_cache_override: None,
-                            timeout: None,
-                        };
-                        let _ = runner_tx.send(RunnerCommand::Run(spec));
+                    if packages.is_empty() {
+                        return;
                     }
-                } else if self.mode == RunMode::Failing {
-                    self.run_failing(runner_tx);
-                } else if self.mode == RunMode::Selected || self.mode == RunMode::Selecting {
-                    self.run_selected(runner_tx);
+                }
+                self.pending_watch
+                    .get_or_insert_with(HashSet::new)
+                    .extend(packages);
+                if self.watch_rerun_due() {
+                    self.start_watch_run(runner_tx);
                 }
             }
             WatchEvent::Error(message) => {
-                self.last_error = Some(message);
+                self.report_error(message);
             }
         }
     }
@@ -268,51 +1308,232 @@ impl App {
         event: Event,
         runner_tx: &crossbeam_channel::Sender<RunnerCommand>,
     ) -> bool {
-        match event {
+        let quit = match event {
             Event::Key(key) => self.handle_key(key, runner_tx),
+            Event::Mouse(mouse) => {
+                self.handle_mouse(mouse);
+                false
+            }
             _ => false,
-        }
+        };
+        self.refresh_lists();
+        quit
     }
 
-    pub fn run_all(&mut self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) {
-        self.cancel_current_run(runner_tx);
-        let packages: Vec<String> = if self.package_filter_active {
-            self.packages
-                .iter()
-                .map(|package| package.import_path.clone())
-                .collect()
-        } else {
-            vec!["./...".to_string()]
-        };
-        if packages.is_empty() {
+    /// Click selects a list row, double-click toggles the output pane and the
+    /// wheel moves the selection.
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if self.help_open || self.detail_search_active || self.preset_prompt.is_some() {
             return;
         }
-        let spec = RunSpec {
-            kind: RunKind::All,
+        if self.package_view && self.mode != RunMode::Selecting {
+            let selected = self.package_list_state.selected().unwrap_or(0);
+            let target = match mouse.kind {
+                MouseEventKind::ScrollUp => selected.saturating_sub(1),
+                MouseEventKind::ScrollDown => selected + 1,
+                MouseEventKind::Down(MouseButton::Left) => {
+                    match self.list_index_at(mouse.column, mouse.row) {
+                        Some(index) => index,
+                        None => return,
+                    }
+                }
+                _ => return,
+            };
+            if target < self.package_summaries().len() {
+                self.package_list_state.select(Some(target));
+            }
+            return;
+        }
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.select_previous(),
+            MouseEventKind::ScrollDown => self.select_next(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let Some(index) = self.list_index_at(mouse.column, mouse.row) else {
+                    return;
+                };
+                let list = self.visible_tests();
+                if index >= list.len() {
+                    return;
+                }
+                self.list_state.select(Some(index));
+                self.selected_test = list.get(index).cloned();
+                self.sync_detail_scroll();
+                let now = Instant::now();
+                let double = self.last_click.is_some_and(|(at, row)| {
+                    row == index && now.duration_since(at) <= DOUBLE_CLICK
+                });
+                if double && self.mode != RunMode::Selecting {
+                    self.detail_open = !self.detail_open;
+                    self.detail_focused = false;
+                    self.last_click = None;
+                } else {
+                    self.last_click = Some((now, index));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn list_index_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.list_area.get();
+        let inside = column > area.x
+            && column + 1 < area.x + area.width
+            && row > area.y
+            && row + 1 < area.y + area.height;
+        inside.then(|| self.list_offset.get() + usize::from(row - area.y - 1))
+    }
+
+    pub fn run_all(&mut self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) {
+        if self.replay_blocks_runs() {
+            return;
+        }
+        self.cancel_current_run(runner_tx);
+        if let Some(spec) = self.run_all_spec() {
+            self.send_run(spec, runner_tx);
+        }
+    }
+
+    /// The spec `run_all` would send, or `None` when every package is muted.
+    pub fn run_all_spec(&self) -> Option<RunSpec> {
+        let packages: Vec<String> = if self.test_all {
+            vec!["all".to_string()]
+        } else if self.package_filter_active || !self.muted_packages.is_empty() {
+            self.packages
+                .iter()
+                .filter(|package| !self.muted_packages.contains(&package.import_path))
+                .map(|package| package.import_path.clone())
+                .collect()
+        } else {
+            self.package_patterns.clone()
+        };
+        if packages.is_empty() {
+            return None;
+        }
+        Some(RunSpec {
+            kind: RunKind::All,
             packages: vec![PackageRun {
                 packages,
                 tests: None,
             }],
             no_test_cache_override: None,
-            timeout: None,
-        };
+            timeout: self.runner_config.run_timeout,
+            go_test_timeout: None,
+            run_pattern: self.run_pattern.clone(),
+        })
+    }
+
+    /// Scopes All-mode runs to tests matching `pattern` and, when it is a
+    /// plain name rather than a regex, starts the select-mode query with it.
+    pub fn set_run_pattern(&mut self, pattern: String) {
+        if regex::escape(&pattern) == pattern {
+            self.selection.query = pattern.clone();
+        }
+        self.run_pattern = Some(pattern);
+        self.refresh_selection_filter();
+        self.refresh_lists();
+    }
+
+    /// Installs the packages listed in the background and starts the initial
+    /// run if it was waiting for them.
+    pub fn set_packages(
+        &mut self,
+        packages: Vec<PackageInfo>,
+        runner_tx: &crossbeam_channel::Sender<RunnerCommand>,
+    ) {
+        // Only the startup run waits on the listing: not once another run
+        // has been started or the view has left All mode.
+        let waiting = self.discovering_packages
+            && self.mode == RunMode::All
+            && self.last_run.is_none()
+            && self.run_all_spec().is_none();
+        self.packages = packages;
+        self.discovering_packages = false;
+        self.refresh_package_labels();
+        if waiting {
+            self.run_all(runner_tx);
+        }
+    }
+
+    /// Whether runs are off because a replay is showing, saying so if they
+    /// are: its results are not from this tree.
+    fn replay_blocks_runs(&mut self) -> bool {
+        if self.replaying {
+            self.info = Some("runs are disabled during a replay".to_string());
+        }
+        self.replaying
+    }
+
+    /// Sends `spec` to the runner and remembers it for `.`.
+    fn send_run(&mut self, spec: RunSpec, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) {
+        self.last_run = Some(spec.clone());
         let _ = runner_tx.send(RunnerCommand::Run(spec));
     }
 
-    pub fn run_failing(&self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) {
+    /// Sends the last run again as it was, whatever the mode or selection.
+    fn rerun_last(&mut self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) {
+        if self.replay_blocks_runs() {
+            return;
+        }
+        let Some(spec) = self.last_run.clone() else {
+            self.report_error("nothing has run yet");
+            return;
+        };
+        self.cancel_current_run(runner_tx);
+        self.send_run(spec, runner_tx);
+    }
+
+    /// Reruns the failing set; returns whether a run was started.
+    pub fn run_failing(&mut self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) -> bool {
+        if self.replay_blocks_runs() {
+            return false;
+        }
         self.cancel_current_run(runner_tx);
         let spec = self.spec_for_tests(RunKind::Failing, &self.failing_set, None);
+        let started = spec.is_some();
         if let Some(spec) = spec {
-            let _ = runner_tx.send(RunnerCommand::Run(spec));
+            self.send_run(spec, runner_tx);
         }
+        started
     }
 
-    pub fn run_selected(&self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) {
+    /// Reruns the selected set; returns whether a run was started.
+    pub fn run_selected(&mut self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) -> bool {
+        if self.replay_blocks_runs() {
+            return false;
+        }
         self.cancel_current_run(runner_tx);
         let spec = self.spec_for_tests(RunKind::Selected, &self.selected_set, None);
+        let started = spec.is_some();
+        if let Some(spec) = spec {
+            self.send_run(spec, runner_tx);
+        }
+        started
+    }
+
+    /// Runs exactly `tests`, as a selected run; returns whether a run was
+    /// started.
+    pub fn run_tests(
+        &mut self,
+        tests: &HashSet<TestId>,
+        runner_tx: &crossbeam_channel::Sender<RunnerCommand>,
+    ) -> bool {
+        if self.replay_blocks_runs() {
+            return false;
+        }
+        self.cancel_current_run(runner_tx);
+        let spec = self.spec_for_tests(RunKind::Selected, tests, None);
+        let started = spec.is_some();
         if let Some(spec) = spec {
-            let _ = runner_tx.send(RunnerCommand::Run(spec));
+            self.send_run(spec, runner_tx);
         }
+        started
+    }
+
+    /// Replaces the selected set, e.g. from an editor over the control
+    /// socket.
+    pub fn set_selected(&mut self, tests: HashSet<TestId>) {
+        self.selected_set = tests;
+        self.refresh_lists();
     }
 
     fn handle_key(
@@ -323,10 +1544,227 @@ impl App {
         if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
             return true;
         }
-        match self.mode {
-            RunMode::Selecting => self.handle_select_key(key, runner_tx),
-            _ => self.handle_list_key(key, runner_tx),
+        self.info = None;
+        let quit_confirmed = self
+            .quit_requested_at
+            .take()
+            .is_some_and(|at| at.elapsed() < QUIT_CONFIRM);
+        if self.loop_until_green && key.code != KeyCode::Char('L') {
+            self.loop_until_green = false;
+        }
+        if self.help_open {
+            let max = self.help_scroll_max.get();
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('?') => self.help_open = false,
+                KeyCode::Up => self.help_scroll = self.help_scroll.saturating_sub(1),
+                KeyCode::Down => self.help_scroll = self.help_scroll.saturating_add(1).min(max),
+                KeyCode::PageUp => self.help_scroll = self.help_scroll.saturating_sub(10),
+                KeyCode::PageDown => {
+                    self.help_scroll = self.help_scroll.saturating_add(10).min(max)
+                }
+                _ => {}
+            }
+            return false;
+        }
+        if self.error_log_open {
+            if matches!(key.code, KeyCode::Esc | KeyCode::Char('E')) {
+                self.error_log_open = false;
+            }
+            return false;
+        }
+        if self.detail_search_active {
+            self.handle_search_key(key);
+            return false;
+        }
+        if self.preset_prompt.is_some() {
+            self.handle_preset_key(key);
+            return false;
+        }
+        if self.mode == RunMode::Selecting {
+            return self.handle_select_key(key, runner_tx);
+        }
+        if self.package_view && self.handle_package_key(key, runner_tx) {
+            return false;
+        }
+        if key.code == KeyCode::Char('q') && self.run_state.running && !quit_confirmed {
+            self.quit_requested_at = Some(Instant::now());
+            return false;
+        }
+        self.handle_list_key(key, runner_tx)
+    }
+
+    /// Whether the "press q again" prompt is showing.
+    pub fn quit_pending(&self) -> bool {
+        self.run_state.running
+            && self
+                .quit_requested_at
+                .is_some_and(|at| at.elapsed() < QUIT_CONFIRM)
+    }
+
+    /// Keys of the package view. Returns `false` for keys it leaves to the
+    /// test list.
+    fn handle_package_key(
+        &mut self,
+        key: KeyEvent,
+        runner_tx: &crossbeam_channel::Sender<RunnerCommand>,
+    ) -> bool {
+        let packages = self.package_summaries();
+        let selected = self
+            .package_list_state
+            .selected()
+            .unwrap_or(0)
+            .min(packages.len().saturating_sub(1));
+        match key.code {
+            KeyCode::Up => self
+                .package_list_state
+                .select(Some(selected.saturating_sub(1))),
+            KeyCode::Down => self
+                .package_list_state
+                .select(Some((selected + 1).min(packages.len().saturating_sub(1)))),
+            KeyCode::Enter => {
+                if let Some(summary) = packages.get(selected) {
+                    self.package_focus = Some(summary.package.clone());
+                    self.package_view = false;
+                    self.list_state.select(Some(0));
+                    self.selected_test = None;
+                    self.refresh_lists();
+                }
+            }
+            KeyCode::Char('r') | KeyCode::Char('P') => {
+                if let Some(summary) = packages.get(selected) {
+                    self.rerun_package(summary.package.clone(), runner_tx);
+                }
+            }
+            KeyCode::Char('m') => {
+                if let Some(summary) = packages.get(selected) {
+                    self.toggle_mute(&summary.package);
+                }
+            }
+            KeyCode::Char('g') | KeyCode::Esc => self.package_view = false,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Mutes `package`, or unmutes it when it already is.
+    fn toggle_mute(&mut self, package: &str) {
+        if !self.muted_packages.remove(package) {
+            self.muted_packages.insert(package.to_string());
+        }
+    }
+
+    fn toggle_package_view(&mut self) {
+        self.package_view = true;
+        if let Some(package) = self.package_focus.take() {
+            let index = self
+                .package_summaries()
+                .iter()
+                .position(|summary| summary.package == package);
+            self.package_list_state.select(index);
+        }
+    }
+
+    fn open_preset_prompt(&mut self, action: PresetAction) {
+        if action == PresetAction::Save && self.selected_set.is_empty() {
+            self.report_error("nothing selected to save as a preset");
+            return;
+        }
+        if action == PresetAction::Load && self.presets.is_empty() {
+            self.report_error("no presets saved yet (S saves the selection)");
+            return;
+        }
+        self.preset_prompt = Some(PresetPrompt {
+            action,
+            name: String::new(),
+        });
+    }
+
+    fn handle_preset_key(&mut self, key: KeyEvent) {
+        let Some(prompt) = self.preset_prompt.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.preset_prompt = None,
+            KeyCode::Backspace => {
+                prompt.name.pop();
+            }
+            KeyCode::Char(ch) => prompt.name.push(ch),
+            KeyCode::Enter => {
+                let name = prompt.name.trim().to_string();
+                if name.is_empty() {
+                    return;
+                }
+                match prompt.action {
+                    PresetAction::Save => self.save_preset(name),
+                    PresetAction::Load => self.load_preset(&name),
+                }
+                self.preset_prompt = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn save_preset(&mut self, name: String) {
+        let mut tests: Vec<TestId> = self.selected_set.iter().cloned().collect();
+        tests.sort_by(|a, b| (&a.package, &a.name).cmp(&(&b.package, &b.name)));
+        self.info = Some(format!("saved preset {} ({} tests)", name, tests.len()));
+        self.presets.insert(name, tests);
+    }
+
+    /// Replaces the selected set with the tests saved under `name`.
+    fn load_preset(&mut self, name: &str) {
+        let Some(tests) = self.presets.get(name).cloned() else {
+            self.report_error(format!("no preset named {}", name));
+            return;
+        };
+        for test in &tests {
+            self.registry.ensure_test(test);
+        }
+        self.info = Some(format!("loaded preset {} ({} tests)", name, tests.len()));
+        self.selected_set = tests.into_iter().collect();
+        self.refresh_lists();
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.detail_search.clear();
+                self.detail_search_active = false;
+            }
+            KeyCode::Enter => self.detail_search_active = false,
+            KeyCode::Backspace => {
+                self.detail_search.pop();
+            }
+            KeyCode::Char(ch) => self.detail_search.push(ch),
+            _ => return,
+        }
+        self.detail_match = None;
+        self.update_search_matches();
+        if !self.detail_search.is_empty() {
+            self.next_search_match();
+        }
+    }
+
+    fn update_search_matches(&mut self) {
+        let text = self.current_test().and_then(|test| self.detail_text(&test));
+        self.detail_matches = search_matches(text.as_deref().unwrap_or(""), &self.detail_search);
+    }
+
+    /// Scrolls the output pane to the next line matching the search, wrapping
+    /// around at the end.
+    fn next_search_match(&mut self) {
+        self.update_search_matches();
+        if self.detail_matches.is_empty() {
+            self.detail_match = None;
+            return;
         }
+        let next = self.detail_match.map_or(0, |index| index + 1) % self.detail_matches.len();
+        self.detail_match = Some(next);
+        let line = self.detail_matches[next];
+        let text = self.current_test().and_then(|test| self.detail_text(&test));
+        let row = wrapped_row(text.as_deref().unwrap_or(""), line, self.detail_width.get());
+        self.detail_scroll = row.min(usize::from(self.detail_scroll_max.get())) as u16;
+        self.tail_paused = true;
     }
 
     fn handle_list_key(
@@ -336,48 +1774,107 @@ impl App {
     ) -> bool {
         match key.code {
             KeyCode::Char('q') => return true,
+            KeyCode::Esc => self.cancel_run(runner_tx),
             KeyCode::Char('a') => {
-                self.mode = RunMode::All;
+                self.switch_mode(RunMode::All);
                 self.run_all(runner_tx);
             }
             KeyCode::Char('o') => {
-                self.mode = RunMode::Failing;
+                self.switch_mode(RunMode::Failing);
                 self.run_failing(runner_tx);
             }
             KeyCode::Char('p') => {
-                self.mode = RunMode::Selecting;
+                self.switch_mode(RunMode::Selecting);
                 self.selection.query.clear();
                 self.refresh_selection_filter();
+                self.reset_selection();
             }
+            KeyCode::Up if self.detail_focused => self.scroll_detail(-1),
+            KeyCode::Down if self.detail_focused => self.scroll_detail(1),
+            KeyCode::PageUp if self.detail_open => self.scroll_detail(-DETAIL_PAGE),
+            KeyCode::PageDown if self.detail_open => self.scroll_detail(DETAIL_PAGE),
             KeyCode::Up => self.select_previous(),
             KeyCode::Down => self.select_next(),
-            KeyCode::Enter => self.detail_open = !self.detail_open,
-            KeyCode::Right => self.detail_open = true,
+            KeyCode::Enter => {
+                self.detail_open = !self.detail_open;
+                self.detail_focused = false;
+            }
+            KeyCode::Right => {
+                self.detail_focused = self.detail_open;
+                self.detail_open = true;
+            }
+            KeyCode::Left if self.detail_focused => self.detail_focused = false,
             KeyCode::Left => self.detail_open = false,
+            KeyCode::Char(' ') => self.toggle_expanded(),
+            KeyCode::Char('g') => self.toggle_package_view(),
+            KeyCode::Char('w') => {
+                self.watch_enabled = !self.watch_enabled;
+                self.pending_watch = None;
+            }
+            KeyCode::Char('v') => self.raw_output = !self.raw_output,
+            KeyCode::Char('|') => self.detail_right = !self.detail_right,
+            KeyCode::Char('y') => self.copy_current_output(),
+            KeyCode::Char('c') => self.copy_run_command(),
+            KeyCode::Char('.') => self.rerun_last(runner_tx),
+            KeyCode::Char('e') => self.open_current_in_editor(),
+            KeyCode::Char('?') => {
+                self.help_open = true;
+                self.help_scroll = 0;
+            }
+            KeyCode::Char('E') => self.error_log_open = true,
+            KeyCode::Char('S') => self.open_preset_prompt(PresetAction::Save),
+            KeyCode::Char('G') => self.open_preset_prompt(PresetAction::Load),
+            KeyCode::Char('i') => self.flip_package_paths = !self.flip_package_paths,
+            KeyCode::Char('I') => {
+                let path = cache_file(&self.repo_root);
+                self.info = Some(format!("cache file: {}", path.display()));
+            }
+            KeyCode::Char('L') => self.toggle_loop_until_green(runner_tx),
+            KeyCode::Char('f') => self.status_filter = self.status_filter.next(),
+            KeyCode::Char('s') => self.sort_order = self.sort_order.next(),
+            KeyCode::Char('F') => self.jump_to_first_failure(),
+            KeyCode::Char('/') if self.detail_open => {
+                self.detail_search.clear();
+                self.detail_matches.clear();
+                self.detail_match = None;
+                self.detail_search_active = true;
+            }
+            KeyCode::Char('n') if self.detail_open && !self.detail_search.is_empty() => {
+                self.next_search_match();
+            }
+            KeyCode::Char('n') => self.select_failure(true),
+            KeyCode::Char('N') => self.select_failure(false),
+            KeyCode::Char('D') if self.debug => self.debug_open = !self.debug_open,
+            KeyCode::Char('m') => {
+                if let Some(test) = self.current_test() {
+                    self.toggle_mute(&test.package);
+                }
+            }
+            KeyCode::Char('M') => self.muted_packages.clear(),
+            KeyCode::Char('l') => {
+                self.follow_failures = !self.follow_failures;
+                self.pending_focus = None;
+            }
             KeyCode::Char('r') | KeyCode::Char('R') => {
                 let no_test_cache = key.modifiers.contains(KeyModifiers::SHIFT)
                     || matches!(key.code, KeyCode::Char('R'));
-                if let Some(test) = self.current_test() {
-                    self.detail_open = false;
-                    self.mark_running(&test);
-                    let mut tests = HashSet::new();
-                    tests.insert(test);
-                    let override_flag = if no_test_cache { Some(true) } else { None };
-                    let spec = self.spec_for_tests(RunKind::Single, &tests, override_flag);
-                    if let Some(spec) = spec {
-                        self.cancel_current_run(runner_tx);
-                        let _ = runner_tx.send(RunnerCommand::Run(spec));
-                    }
-                }
+                self.rerun_current(runner_tx, no_test_cache, None);
+            }
+            KeyCode::Char('P') => self.rerun_current_package(runner_tx),
+            KeyCode::Char('C') => self.clear_finished_output(),
+            KeyCode::Char('d') if self.detail_open => self.detail_diff = !self.detail_diff,
+            KeyCode::Char('t') => self.toggle_tail(),
+            KeyCode::Char('T') if self.detail_open => {
+                self.rerun_current(runner_tx, false, Some(EXTENDED_GO_TEST_TIMEOUT));
             }
             KeyCode::Char('x') => {
                 if let Some(test) = self.current_test() {
                     match self.mode {
                         RunMode::Failing => {
-                            self.failing_set.remove(&test);
+                            self.failing_set.retain(|id| !is_within(id, &test));
                         }
                         RunMode::Selected => {
-                            self.selected_set.remove(&test);
+                            self.selected_set.retain(|id| !is_within(id, &test));
                         }
                         _ => {}
                     }
@@ -389,23 +1886,120 @@ impl App {
         false
     }
 
+    fn rerun_current(
+        &mut self,
+        runner_tx: &crossbeam_channel::Sender<RunnerCommand>,
+        no_test_cache: bool,
+        go_test_timeout: Option<Duration>,
+    ) {
+        if self.replay_blocks_runs() {
+            return;
+        }
+        if let Some(test) = self.current_test() {
+            self.detail_open = false;
+            self.detail_focused = false;
+            self.detail_scroll = 0;
+            self.mark_running(&test);
+            // A parent reruns all of its subtests.
+            if self.registry.is_parent(&test) {
+                for leaf in self.registry.leaf_tests() {
+                    if is_within(&leaf, &test) {
+                        self.mark_running(&leaf);
+                    }
+                }
+            }
+            let mut tests = HashSet::new();
+            tests.insert(test);
+            let override_flag = if no_test_cache { Some(true) } else { None };
+            let spec = self.spec_for_tests(RunKind::Single, &tests, override_flag);
+            if let Some(mut spec) = spec {
+                spec.go_test_timeout = go_test_timeout;
+                self.cancel_current_run(runner_tx);
+                self.send_run(spec, runner_tx);
+            }
+        }
+    }
+
+    /// Reruns the whole package of the test under the cursor, without a
+    /// `-run` filter.
+    fn rerun_current_package(&mut self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) {
+        if let Some(package) = self.current_test().map(|test| test.package) {
+            self.rerun_package(package, runner_tx);
+        }
+    }
+
+    fn rerun_package(
+        &mut self,
+        package: String,
+        runner_tx: &crossbeam_channel::Sender<RunnerCommand>,
+    ) {
+        if self.replay_blocks_runs() {
+            return;
+        }
+        let tests = self.registry.leaf_tests();
+        for test in tests.iter().filter(|test| test.package == package) {
+            self.mark_running(test);
+        }
+        let spec = RunSpec {
+            kind: RunKind::Selected,
+            packages: vec![PackageRun {
+                packages: vec![package],
+                tests: None,
+            }],
+            no_test_cache_override: None,
+            timeout: self.runner_config.run_timeout,
+            go_test_timeout: None,
+            run_pattern: None,
+        };
+        self.cancel_current_run(runner_tx);
+        self.send_run(spec, runner_tx);
+    }
+
+    /// Clears stale output and forgets passed tests; the failing and selected
+    /// sets keep their members.
+    fn clear_finished_output(&mut self) {
+        if self.run_state.running {
+            self.report_error("wait for the run to finish before clearing");
+            return;
+        }
+        self.registry.clear_finished();
+        // Cleared tests still exist; keep their history.
+        self.full_run_finished = false;
+        self.detail_scroll = 0;
+        self.refresh_lists();
+    }
+
     fn handle_select_key(
         &mut self,
         key: KeyEvent,
         runner_tx: &crossbeam_channel::Sender<RunnerCommand>,
     ) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
         match key.code {
+            KeyCode::Enter | KeyCode::Char('r') if ctrl => {
+                self.select_all_filtered();
+                self.switch_mode(RunMode::Selected);
+                self.refresh_lists();
+                self.run_selected(runner_tx);
+            }
+            KeyCode::Char('a') if ctrl => self.select_all_filtered(),
+            KeyCode::Char('u') if ctrl => self.unselect_all_filtered(),
+            KeyCode::Char('s') if ctrl => {
+                self.selection.fuzzy.case = self.selection.fuzzy.case.next();
+                self.refresh_selection_filter();
+                self.reset_selection();
+            }
             KeyCode::Esc | KeyCode::Char('p') => {
-                self.mode = RunMode::Selected;
+                self.switch_mode(RunMode::Selected);
                 self.refresh_lists();
                 self.run_selected(runner_tx);
             }
             KeyCode::Char('a') => {
-                self.mode = RunMode::All;
+                self.switch_mode(RunMode::All);
                 self.run_all(runner_tx);
             }
             KeyCode::Char('o') => {
-                self.mode = RunMode::Failing;
+                self.switch_mode(RunMode::Failing);
                 self.run_failing(runner_tx);
             }
             KeyCode::Up => self.select_previous(),
@@ -422,12 +2016,14 @@ impl App {
             KeyCode::Backspace => {
                 self.selection.query.pop();
                 self.refresh_selection_filter();
+                self.reset_selection();
             }
-            KeyCode::Char(ch) => {
-                if key.modifiers.is_empty() || key.modifiers == KeyModifiers::SHIFT {
-                    self.selection.query.push(ch);
-                    self.refresh_selection_filter();
-                }
+            KeyCode::Char(ch)
+                if key.modifiers.is_empty() || key.modifiers == KeyModifiers::SHIFT =>
+            {
+                self.selection.query.push(ch);
+                self.refresh_selection_filter();
+                self.reset_selection();
             }
             _ => {}
         }
@@ -435,6 +2031,17 @@ impl App {
         false
     }
 
+    fn select_all_filtered(&mut self) {
+        self.selected_set
+            .extend(self.selection.filtered.iter().cloned());
+    }
+
+    fn unselect_all_filtered(&mut self) {
+        for test in &self.selection.filtered {
+            self.selected_set.remove(test);
+        }
+    }
+
     fn select_previous(&mut self) {
         let list = self.visible_tests();
         if list.is_empty() {
@@ -448,6 +2055,29 @@ impl App {
             index - 1
         };
         self.list_state.select(Some(new_index));
+        self.selected_test = list.get(new_index).cloned();
+    }
+
+    /// Moves the cursor to the next (`forward`) or previous failed test in
+    /// the visible list, wrapping around. Does nothing without failures.
+    fn select_failure(&mut self, forward: bool) {
+        let list = self.visible_tests();
+        let len = list.len();
+        let current = self.list_state.selected().unwrap_or(0);
+        let parents = self.parent_statuses();
+        let target = (1..=len)
+            .map(|step| {
+                if forward {
+                    (current + step) % len
+                } else {
+                    (current + len - step % len) % len
+                }
+            })
+            .find(|&index| self.row_status(&list[index], &parents) == TestStatus::Failed);
+        if let Some(index) = target {
+            self.list_state.select(Some(index));
+            self.selected_test = list.get(index).cloned();
+        }
     }
 
     fn select_next(&mut self) {
@@ -463,46 +2093,222 @@ impl App {
             index + 1
         };
         self.list_state.select(Some(new_index));
+        self.selected_test = list.get(new_index).cloned();
     }
 
-    fn refresh_lists(&mut self) {
-        let list = self.visible_tests();
-        self.ensure_selection_index(&list);
+    fn failed_leaf(&self, event: &GoTestEvent) -> Option<TestId> {
+        if event.action != GoTestAction::Fail {
+            return None;
+        }
+        let id = TestId {
+            package: event.package.clone(),
+            name: event.test.clone()?,
+        };
+        (!self.registry.is_parent(&id)).then_some(id)
     }
 
-    fn ensure_selection_index(&mut self, list: &[TestId]) {
-        let current = self.list_state.selected().unwrap_or(0);
-        if list.is_empty() {
-            self.list_state.select(None);
+    /// Moves the cursor to the newest failure while follow mode is on, at
+    /// most once per `FOLLOW_FAILURE_DEBOUNCE` so bursts don't thrash it.
+    fn apply_pending_focus(&mut self) {
+        if !self.follow_failures || self.pending_focus.is_none() {
             return;
         }
-        if current >= list.len() || self.list_state.selected().is_none() {
-            self.list_state.select(Some(0));
+        if let Some(last) = self.last_auto_focus {
+            if last.elapsed() < FOLLOW_FAILURE_DEBOUNCE {
+                return;
+            }
+        }
+        if let Some(test) = self.pending_focus.take() {
+            if self.select_test(&test) {
+                self.detail_open = true;
+                self.last_auto_focus = Some(Instant::now());
+                // Selecting may have expanded its parents.
+                self.refresh_lists();
+            }
         }
     }
 
-    fn update_failing_set(&mut self) {
-        let failing = self.registry.failed_tests();
-        self.failing_set = failing.into_iter().collect();
+    fn jump_to_first_failure(&mut self) {
+        let Some(test) = self.run_state.first_failure.clone() else {
+            return;
+        };
+        if !self.select_test(&test) {
+            self.report_error(format!("first failure {} is not in this view", test));
+        }
     }
 
-    fn sorted_all_tests(&self) -> Vec<TestId> {
-        let mut tests = self.registry.leaf_tests();
-        tests.sort_by(|a, b| {
-            let status_a = self.status_rank(a);
-            let status_b = self.status_rank(b);
-            status_a.cmp(&status_b).then_with(|| {
-                self.registry
-                    .order_index(a)
-                    .cmp(&self.registry.order_index(b))
-            })
-        });
-        tests
+    /// Moves the cursor to `id`, expanding its parents so it is listed.
+    fn select_test(&mut self, id: &TestId) -> bool {
+        self.expanded.extend(self.ancestors(id));
+        match self.visible_tests().iter().position(|test| test == id) {
+            Some(index) => {
+                self.list_state.select(Some(index));
+                self.selected_test = Some(id.clone());
+                true
+            }
+            None => false,
+        }
     }
 
-    fn sorted_from_set(&self, set: &HashSet<TestId>) -> Vec<TestId> {
-        let mut tests: Vec<TestId> = set
-            .iter()
+    /// Recomputes the listed tests and rows, sorting them once here rather
+    /// than on every draw, and keeps the cursor on the selected test.
+    pub fn refresh_lists(&mut self) {
+        self.refresh_package_labels();
+        self.listed = self.listed_tests();
+        self.rows = self.rows_of(self.listed.clone());
+        let list = self.rows.clone();
+        let tracked = self
+            .selected_test
+            .as_ref()
+            .and_then(|id| list.iter().position(|test| test == id));
+        if let Some(index) = tracked {
+            self.list_state.select(Some(index));
+        }
+        self.ensure_selection_index(&list);
+        self.selected_test = self
+            .list_state
+            .selected()
+            .and_then(|index| list.get(index).cloned());
+        self.sync_detail_scroll();
+    }
+
+    fn refresh_package_labels(&mut self) {
+        let mut packages = self.registry.packages();
+        packages.extend(self.packages.iter().map(|info| info.import_path.as_str()));
+        if packages
+            .iter()
+            .all(|package| self.package_labels.contains_key(*package))
+        {
+            return;
+        }
+        self.package_labels = short_package_labels(&packages);
+    }
+
+    /// How `package` is named: its full import path when `full`, otherwise
+    /// its shortest unambiguous trailing segments (just the last one until
+    /// the lists are next refreshed).
+    pub fn package_label<'a>(&'a self, package: &'a str, full: bool) -> &'a str {
+        match self.package_labels.get(package) {
+            _ if full => package,
+            Some(label) => label,
+            None => package_suffixes(package).next().unwrap_or(package),
+        }
+    }
+
+    /// Whether the output pane is pinned to the end of the output.
+    pub fn tailing(&self) -> bool {
+        self.tail
+            && !self.tail_paused
+            && self
+                .current_test()
+                .and_then(|test| self.registry.case(&test).map(|case| case.status))
+                == Some(TestStatus::Running)
+    }
+
+    fn toggle_tail(&mut self) {
+        self.tail = !self.tail || self.tail_paused;
+        self.tail_paused = false;
+    }
+
+    fn scroll_detail(&mut self, delta: i32) {
+        if delta < 0 && self.tailing() {
+            self.detail_scroll = self.detail_scroll_max.get();
+            self.tail_paused = true;
+        }
+        let max = i32::from(self.detail_scroll_max.get());
+        self.detail_scroll = (i32::from(self.detail_scroll) + delta).clamp(0, max) as u16;
+    }
+
+    /// Scrolls the output pane back to the top once the cursor moves to a
+    /// different test, and keeps the search matches in step with the output
+    /// shown, which changes with the test or as output arrives.
+    fn sync_detail_scroll(&mut self) {
+        if self.detail_scroll_test != self.selected_test {
+            self.detail_scroll = 0;
+            self.tail_paused = false;
+            self.detail_scroll_test = self.selected_test.clone();
+            self.detail_match = None;
+        }
+        if !self.detail_search.is_empty() {
+            self.update_search_matches();
+        }
+    }
+
+    /// Switches to `mode`, restoring the cursor to the test that was selected
+    /// there last time if it is still listed, and to the top otherwise.
+    fn switch_mode(&mut self, mode: RunMode) {
+        if mode == self.mode {
+            return;
+        }
+        if let Some(test) = self.selected_test.take() {
+            self.mode_selection.insert(self.mode, test);
+        }
+        self.mode = mode;
+        self.selected_test = self.mode_selection.get(&mode).cloned();
+        self.list_state.select(Some(0));
+        self.refresh_lists();
+    }
+
+    fn reset_selection(&mut self) {
+        self.selected_test = None;
+        self.list_state.select(Some(0));
+    }
+
+    fn ensure_selection_index(&mut self, list: &[TestId]) {
+        let current = self.list_state.selected().unwrap_or(0);
+        if list.is_empty() {
+            self.list_state.select(None);
+            return;
+        }
+        if current >= list.len() || self.list_state.selected().is_none() {
+            self.list_state.select(Some(0));
+        }
+    }
+
+    fn update_failing_set(&mut self) {
+        let failing = self.registry.failed_tests();
+        self.failing_set = failing.into_iter().collect();
+    }
+
+    fn sorted_all_tests(&self) -> Vec<TestId> {
+        let mut tests = self.registry.leaf_tests();
+        if self.status_filter != StatusFilter::All {
+            tests.retain(|test| {
+                let status = self
+                    .registry
+                    .case(test)
+                    .map(|case| case.status)
+                    .unwrap_or(TestStatus::Unknown);
+                self.status_filter.matches(status)
+            });
+        }
+        tests.sort_by(|a, b| {
+            let primary = match self.sort_order {
+                SortOrder::Status => self.status_rank(a).cmp(&self.status_rank(b)),
+                // Slowest first; tests without a recorded duration go last.
+                SortOrder::Duration => {
+                    let duration =
+                        |test: &TestId| self.registry.case(test).and_then(|case| case.duration);
+                    match (duration(a), duration(b)) {
+                        (Some(a), Some(b)) => b.cmp(&a),
+                        (Some(_), None) => std::cmp::Ordering::Less,
+                        (None, Some(_)) => std::cmp::Ordering::Greater,
+                        (None, None) => std::cmp::Ordering::Equal,
+                    }
+                }
+            };
+            primary.then_with(|| {
+                self.registry
+                    .order_index(a)
+                    .cmp(&self.registry.order_index(b))
+            })
+        });
+        tests
+    }
+
+    fn sorted_from_set(&self, set: &HashSet<TestId>) -> Vec<TestId> {
+        let mut tests: Vec<TestId> = set
+            .iter()
             .filter(|id| !self.registry.is_parent(id))
             .cloned()
             .collect();
@@ -510,6 +2316,57 @@ impl App {
         tests
     }
 
+    /// Orders `leaves` so siblings stay together under their parents and
+    /// inserts the parent rows; subtests of collapsed parents are left out.
+    fn tree_rows(&self, leaves: Vec<TestId>) -> Vec<TestId> {
+        let chains: Vec<Vec<TestId>> = leaves
+            .into_iter()
+            .map(|leaf| {
+                let mut chain = self.ancestors(&leaf);
+                chain.push(leaf);
+                chain
+            })
+            .collect();
+        let mut first_seen: HashMap<&TestId, usize> = HashMap::new();
+        for (index, chain) in chains.iter().enumerate() {
+            for id in chain {
+                first_seen.entry(id).or_insert(index);
+            }
+        }
+        let mut order: Vec<(Vec<usize>, usize)> = chains
+            .iter()
+            .enumerate()
+            .map(|(index, chain)| (chain.iter().map(|id| first_seen[id]).collect(), index))
+            .collect();
+        order.sort();
+
+        let mut rows = Vec::new();
+        let mut shown = HashSet::new();
+        for (_, index) in order {
+            for id in &chains[index] {
+                if shown.insert(id) {
+                    rows.push(id.clone());
+                }
+                if !self.expanded.contains(id) {
+                    break;
+                }
+            }
+        }
+        rows
+    }
+
+    fn toggle_expanded(&mut self) {
+        let Some(test) = self.current_test() else {
+            return;
+        };
+        if !self.registry.is_parent(&test) {
+            return;
+        }
+        if !self.expanded.remove(&test) {
+            self.expanded.insert(test);
+        }
+    }
+
     fn status_rank(&self, id: &TestId) -> usize {
         let status = self
             .registry
@@ -520,7 +2377,8 @@ impl App {
             TestStatus::Failed => 0,
             TestStatus::Running => 1,
             TestStatus::Passed => 2,
-            TestStatus::Unknown => 3,
+            TestStatus::Skipped => 3,
+            TestStatus::Unknown => 4,
         }
     }
 
@@ -530,16 +2388,19 @@ impl App {
         tests: &HashSet<TestId>,
         no_test_cache_override: Option<bool>,
     ) -> Option<RunSpec> {
-        if tests.is_empty() {
-            return None;
-        }
         let mut packages: HashMap<String, Vec<String>> = HashMap::new();
         for test in tests {
+            if self.muted_packages.contains(&test.package) {
+                continue;
+            }
             packages
                 .entry(test.package.clone())
                 .or_default()
                 .push(test.name.clone());
         }
+        if packages.is_empty() {
+            return None;
+        }
         Some(RunSpec {
             kind,
             packages: packages
@@ -550,21 +2411,42 @@ impl App {
                 })
                 .collect(),
             no_test_cache_override,
-            timeout: None,
+            timeout: self.runner_config.run_timeout,
+            go_test_timeout: None,
+            run_pattern: None,
         })
     }
 
     fn refresh_selection_filter(&mut self) {
-        let query = self.selection.query.clone();
-        let all_tests = self.registry.leaf_tests();
+        let (package_filters, query) = split_package_filters(&self.selection.query);
+        let mut all_tests = self.registry.leaf_tests();
+        all_tests.retain(|test| {
+            !self.muted_packages.contains(&test.package)
+                && package_filters
+                    .iter()
+                    .all(|filter| test.package.to_lowercase().contains(filter))
+        });
+        self.selection.positions.clear();
         let filtered = if query.is_empty() {
             all_tests
         } else {
-            let matcher = SkimMatcherV2::default();
+            let fuzzy = self.selection.fuzzy;
+            let matcher = match fuzzy.case {
+                FuzzyCase::Smart => SkimMatcherV2::default().smart_case(),
+                FuzzyCase::Respect => SkimMatcherV2::default().respect_case(),
+                FuzzyCase::Ignore => SkimMatcherV2::default().ignore_case(),
+            };
             let mut scored: Vec<(i64, TestId)> = Vec::new();
             for test in all_tests {
-                let haystack = format!("{} {}", test.name, test.package);
-                if let Some(score) = matcher.fuzzy_match(&haystack, &query) {
+                let haystack = if fuzzy.name_only || !package_filters.is_empty() {
+                    test.name.clone()
+                } else {
+                    format!("{} {}", test.name, test.package)
+                };
+                if let Some((score, mut positions)) = matcher.fuzzy_indices(&haystack, &query) {
+                    let name_len = test.name.chars().count();
+                    positions.retain(|&position| position < name_len);
+                    self.selection.positions.insert(test.clone(), positions);
                     scored.push((score, test));
                 }
             }
@@ -579,17 +2461,32 @@ impl App {
         };
 
         self.selection.filtered = filtered;
-        self.list_state.select(Some(0));
     }
 
     fn mark_running(&mut self, id: &TestId) {
         self.registry.ensure_test(id);
         if let Some(case) = self.registry.case_mut(id) {
+            case.reset_output();
             case.status = TestStatus::Running;
-            case.output.clear();
             case.panic = false;
+            case.race = false;
             case.last_update = Some(Instant::now());
+            case.started_at = case.last_update;
+            case.duration = None;
+        }
+    }
+
+    /// Stops the active run and keeps its results as they are.
+    pub fn cancel_run(&mut self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) {
+        if self.replay_blocks_runs() {
+            return;
+        }
+        if !self.run_state.running {
+            self.info = Some("no run to cancel".to_string());
+            return;
         }
+        self.cancel_current_run(runner_tx);
+        self.run_state.cancelled = true;
     }
 
     fn cancel_current_run(&self, runner_tx: &crossbeam_channel::Sender<RunnerCommand>) {
@@ -608,6 +2505,45 @@ impl App {
     }
 }
 
+/// Line diff of a test's previous and current output, one `-`, `+` or
+/// space prefixed line per output line.
+pub fn output_diff(previous: &str, current: &str) -> String {
+    if previous.is_empty() && current.is_empty() {
+        return String::new();
+    }
+    let mut diff = String::from("--- previous run\n+++ this run\n");
+    for change in similar::TextDiff::from_lines(previous, current).iter_all_changes() {
+        let sign = match change.tag() {
+            similar::ChangeTag::Delete => '-',
+            similar::ChangeTag::Insert => '+',
+            similar::ChangeTag::Equal => ' ',
+        };
+        diff.push(sign);
+        diff.push(' ');
+        diff.push_str(change.value().trim_end_matches('\n'));
+        diff.push('\n');
+    }
+    diff
+}
+
+/// Splits `pkg:<fragment>` tokens off a select-mode query. Returns the
+/// lowercased fragments, which a test's package must all contain, and the
+/// rest of the query for the fuzzy match on names.
+fn split_package_filters(query: &str) -> (Vec<String>, String) {
+    let mut filters = Vec::new();
+    let mut rest = Vec::new();
+    for token in query.split_whitespace() {
+        match token.strip_prefix("pkg:") {
+            Some(fragment) => filters.push(fragment.to_lowercase()),
+            None => rest.push(token),
+        }
+    }
+    if filters.is_empty() {
+        return (filters, query.to_string());
+    }
+    (filters, rest.join(" "))
+}
+
 fn is_go_file(path: &std::path::Path) -> bool {
     match path.extension().and_then(|ext| ext.to_str()) {
         Some("go") => true,
@@ -617,12 +2553,79 @@ fn is_go_file(path: &std::path::Path) -> bool {
     }
 }
 
+/// The fewest trailing segments of each package that no other package ends
+/// with, e.g. `api` for `example.com/svc/api`, or `v2/api` when there is also
+/// a `v1/api`.
+fn short_package_labels(packages: &HashSet<&str>) -> HashMap<String, String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for package in packages {
+        for suffix in package_suffixes(package) {
+            *counts.entry(suffix).or_default() += 1;
+        }
+    }
+    packages
+        .iter()
+        .map(|package| {
+            let label = package_suffixes(package)
+                .find(|suffix| counts[suffix] == 1)
+                .unwrap_or(package);
+            (package.to_string(), label.to_string())
+        })
+        .collect()
+}
+
+/// Trailing segments of `package`, shortest first, ending with all of it.
+fn package_suffixes(package: &str) -> impl Iterator<Item = &str> {
+    package
+        .rmatch_indices('/')
+        .map(|(index, _)| &package[index + 1..])
+        .chain(std::iter::once(package))
+}
+
+/// Whether `test` is `parent` itself or one of its subtests.
+fn is_within(test: &TestId, parent: &TestId) -> bool {
+    test.package == parent.package
+        && test
+            .name
+            .strip_prefix(parent.name.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Indices of the lines in `text` containing `query`, ignoring case.
+pub fn search_matches(text: &str, query: &str) -> Vec<usize> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let query = query.to_lowercase();
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| line.to_lowercase().contains(&query))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Row at which `line` of `text` starts once wrapped to `width` columns.
+fn wrapped_row(text: &str, line: usize, width: u16) -> usize {
+    if width == 0 {
+        return line;
+    }
+    text.lines()
+        .take(line)
+        .map(|line| {
+            let paragraph = Paragraph::new(line).wrap(Wrap { trim: false });
+            paragraph.line_count(width)
+        })
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cache::CacheState;
+    use crate::cache::{BranchState, CacheState};
     use crate::go::{GoTestAction, GoTestEvent};
+    use crate::model::PACKAGE_BUILD_TEST;
     use crate::repo::PackageInfo;
+    use crate::runner::REPLAY_RUN_ID;
 
     fn sample_app() -> App {
         App::new(
@@ -647,6 +2650,7 @@ mod tests {
             test: Some("TestFoo".to_string()),
             output: None,
             elapsed: None,
+            ..Default::default()
         };
         app.registry.apply_event(&event);
         app.update_failing_set();
@@ -665,6 +2669,7 @@ mod tests {
             test: Some("TestAlpha".to_string()),
             output: None,
             elapsed: None,
+            ..Default::default()
         };
         app.registry.apply_event(&event);
         app.selection.query = "Tal".to_string();
@@ -673,6 +2678,36 @@ mod tests {
         assert_eq!(app.selection.filtered[0].name, "TestAlpha");
     }
 
+    #[test]
+    fn fuzzy_filter_honors_case_and_name_only_options() {
+        let mut app = sample_app();
+        for (package, name) in [("example/auth", "TestLogin"), ("example", "TestAuth")] {
+            app.registry.apply_event(&GoTestEvent {
+                action: GoTestAction::Run,
+                package: package.to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+
+        app.selection.query = "auth".to_string();
+        app.refresh_selection_filter();
+        assert_eq!(app.selection.filtered.len(), 2);
+
+        app.selection.fuzzy.case = FuzzyCase::Respect;
+        app.refresh_selection_filter();
+        assert_eq!(app.selection.filtered.len(), 1);
+        assert_eq!(app.selection.filtered[0].name, "TestLogin");
+
+        app.selection.fuzzy.case = FuzzyCase::Ignore;
+        app.selection.fuzzy.name_only = true;
+        app.refresh_selection_filter();
+        assert_eq!(app.selection.filtered.len(), 1);
+        assert_eq!(app.selection.filtered[0].name, "TestAuth");
+    }
+
     #[test]
     fn sorts_failures_first_in_all_mode() {
         let mut app = sample_app();
@@ -682,6 +2717,7 @@ mod tests {
             test: Some("TestFail".to_string()),
             output: None,
             elapsed: None,
+            ..Default::default()
         };
         let pass = GoTestEvent {
             action: GoTestAction::Pass,
@@ -689,6 +2725,7 @@ mod tests {
             test: Some("TestPass".to_string()),
             output: None,
             elapsed: None,
+            ..Default::default()
         };
         app.registry.apply_event(&pass);
         app.registry.apply_event(&fail);
@@ -697,40 +2734,1946 @@ mod tests {
     }
 
     #[test]
-    fn groups_spec_by_package() {
-        let app = sample_app();
-        let mut tests = HashSet::new();
-        tests.insert(TestId {
-            package: "example".to_string(),
-            name: "TestFoo".to_string(),
+    fn remembers_first_failure_of_current_run() {
+        let mut app = sample_app();
+        app.handle_runner_event(RunnerEvent::RunStarted {
+            run_id: 1,
+            kind: RunKind::All,
+            packages: 1,
         });
-        tests.insert(TestId {
-            package: "example".to_string(),
-            name: "TestBar".to_string(),
+        for (action, name) in [
+            (GoTestAction::Pass, "TestPass"),
+            (GoTestAction::Fail, "TestFirst"),
+            (GoTestAction::Fail, "TestSecond"),
+        ] {
+            app.handle_runner_event(RunnerEvent::TestEvent {
+                run_id: 1,
+                event: GoTestEvent {
+                    action,
+                    package: "example".to_string(),
+                    test: Some(name.to_string()),
+                    output: None,
+                    elapsed: None,
+                    ..Default::default()
+                },
+            });
+        }
+        assert_eq!(
+            app.run_state
+                .first_failure
+                .as_ref()
+                .map(|id| id.name.as_str()),
+            Some("TestFirst")
+        );
+
+        app.jump_to_first_failure();
+        assert_eq!(app.current_test().unwrap().name, "TestFirst");
+
+        app.handle_runner_event(RunnerEvent::RunStarted {
+            run_id: 2,
+            kind: RunKind::All,
+            packages: 1,
         });
-        let spec = app.spec_for_tests(RunKind::Selected, &tests, None).unwrap();
+        assert!(app.run_state.first_failure.is_none());
+    }
+
+    #[test]
+    fn selection_follows_test_across_resort() {
+        let mut app = sample_app();
+        for name in ["TestA", "TestB"] {
+            app.registry.apply_event(&GoTestEvent {
+                action: GoTestAction::Pass,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        app.refresh_lists();
+        app.select_next();
+        assert_eq!(app.current_test().unwrap().name, "TestB");
+
+        app.handle_runner_event(RunnerEvent::TestEvent {
+            run_id: 1,
+            event: GoTestEvent {
+                action: GoTestAction::Fail,
+                package: "example".to_string(),
+                test: Some("TestB".to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            },
+        });
+        assert_eq!(app.list_state.selected(), Some(0));
+        assert_eq!(app.current_test().unwrap().name, "TestB");
+    }
+
+    #[test]
+    fn test_all_runs_all_pattern() {
+        let mut app = sample_app();
+        app.test_all = true;
+        let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+        app.run_all(&runner_tx);
+        match runner_rx.recv().unwrap() {
+            RunnerCommand::Run(spec) => {
+                assert_eq!(spec.packages.len(), 1);
+                assert_eq!(spec.packages[0].packages, vec!["all".to_string()]);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn muted_packages_are_hidden_and_not_run() {
+        let mut app = sample_app();
+        for package in ["example", "example/noisy"] {
+            app.registry.apply_event(&GoTestEvent {
+                action: GoTestAction::Fail,
+                package: package.to_string(),
+                test: Some("TestFoo".to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        app.muted_packages.insert("example/noisy".to_string());
+
+        let visible = app.visible_tests();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].package, "example");
+
+        let tests: HashSet<TestId> = app.registry.failed_tests().into_iter().collect();
+        let spec = app.spec_for_tests(RunKind::Failing, &tests, None).unwrap();
         assert_eq!(spec.packages.len(), 1);
-        assert_eq!(spec.packages[0].tests.as_ref().unwrap().len(), 2);
-        assert_eq!(spec.packages[0].packages.len(), 1);
+        assert_eq!(spec.packages[0].packages, vec!["example".to_string()]);
+
+        let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+        app.run_all(&runner_tx);
+        match runner_rx.recv().unwrap() {
+            RunnerCommand::Run(spec) => {
+                assert_eq!(spec.packages[0].packages, vec!["example".to_string()]);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert_eq!(app.cache_state().muted_packages, vec!["example/noisy"]);
     }
 
     #[test]
-    fn mark_running_clears_output() {
+    fn focused_detail_scrolls_and_resets_on_selection_change() {
         let mut app = sample_app();
-        let id = TestId {
+        for name in ["TestA", "TestB"] {
+            app.registry.apply_event(&GoTestEvent {
+                action: GoTestAction::Pass,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        app.refresh_lists();
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let press = |app: &mut App, code| {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx);
+        };
+        app.detail_scroll_max.set(12);
+
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Right);
+        assert!(app.detail_focused);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::PageDown);
+        assert_eq!(app.detail_scroll, 11);
+        press(&mut app, KeyCode::PageDown);
+        assert_eq!(app.detail_scroll, 12);
+        assert_eq!(app.current_test().unwrap().name, "TestA");
+
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.current_test().unwrap().name, "TestB");
+        assert_eq!(app.detail_scroll, 0);
+    }
+
+    #[test]
+    fn copy_without_selection_reports_instead_of_copying() {
+        let mut app = sample_app();
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let key = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+        app.handle_input(Event::Key(key), &runner_tx);
+        assert_eq!(app.last_error(), Some("no test selected, nothing to copy"));
+        assert!(app.clipboard.is_none());
+    }
+
+    #[test]
+    fn counts_visible_tests_by_status() {
+        let mut app = sample_app();
+        let event = |action, name: &str| GoTestEvent {
+            action,
             package: "example".to_string(),
-            name: "TestFoo".to_string(),
+            test: Some(name.to_string()),
+            output: None,
+            elapsed: None,
+            ..Default::default()
         };
-        app.registry.ensure_test(&id);
-        if let Some(case) = app.registry.case_mut(&id) {
-            case.output = "old output".to_string();
-            case.status = TestStatus::Failed;
-            case.panic = true;
+        for (action, name) in [
+            (GoTestAction::Pass, "TestA"),
+            (GoTestAction::Fail, "TestB"),
+            (GoTestAction::Run, "TestC"),
+        ] {
+            app.registry.apply_event(&event(action, name));
         }
-        app.mark_running(&id);
-        let case = app.registry.case(&id).unwrap();
-        assert_eq!(case.status, TestStatus::Running);
-        assert!(case.output.is_empty());
-        assert!(!case.panic);
+        app.update_failing_set();
+        app.refresh_lists();
+
+        let counts = app.status_counts();
+        assert_eq!((counts.passed, counts.failed, counts.running), (1, 1, 1));
+        assert_eq!(app.rows(), app.visible_tests());
+
+        app.mode = RunMode::Failing;
+        app.refresh_lists();
+        let counts = app.status_counts();
+        assert_eq!((counts.passed, counts.failed, counts.running), (0, 1, 0));
+    }
+
+    #[test]
+    fn status_filter_narrows_all_mode() {
+        let mut app = sample_app();
+        for (action, name) in [
+            (GoTestAction::Pass, "TestA"),
+            (GoTestAction::Fail, "TestB"),
+            (GoTestAction::Run, "TestC"),
+        ] {
+            app.registry.apply_event(&GoTestEvent {
+                action,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        let names = |app: &App| -> Vec<String> {
+            let tests = app.visible_tests();
+            tests.into_iter().map(|test| test.name).collect()
+        };
+        assert_eq!(names(&app).len(), 3);
+
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let steps = [
+            (StatusFilter::Failed, "TestB"),
+            (StatusFilter::Passed, "TestA"),
+            (StatusFilter::Running, "TestC"),
+        ];
+        for (filter, name) in steps {
+            let key = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx);
+            assert_eq!(app.status_filter, filter);
+            assert_eq!(names(&app), vec![name]);
+        }
+        assert_eq!(app.mode, RunMode::All);
+    }
+
+    #[test]
+    fn n_and_shift_n_cycle_through_failures() {
+        let mut app = sample_app();
+        app.mode = RunMode::Selected;
+        for (action, name) in [
+            (GoTestAction::Fail, "TestA"),
+            (GoTestAction::Pass, "TestB"),
+            (GoTestAction::Fail, "TestC"),
+            (GoTestAction::Pass, "TestD"),
+        ] {
+            let event = GoTestEvent {
+                action,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            };
+            app.registry.apply_event(&event);
+            app.selected_set.insert(TestId {
+                package: event.package,
+                name: name.to_string(),
+            });
+        }
+        app.refresh_lists();
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let mut press = |ch| {
+            let key = KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx);
+            app.current_test().unwrap().name
+        };
+        assert_eq!(press('n'), "TestC");
+        assert_eq!(press('n'), "TestA");
+        assert_eq!(press('N'), "TestC");
+        assert_eq!(press('N'), "TestA");
+    }
+
+    #[test]
+    fn loads_and_saves_sets_for_the_current_branch() {
+        let test = |name: &str| TestId {
+            package: "example".to_string(),
+            name: name.to_string(),
+        };
+        let mut cache = CacheState {
+            failing: vec![test("TestDetached")],
+            branch: Some("feature".to_string()),
+            ..Default::default()
+        };
+        for (branch, name) in [("main", "TestMain"), ("feature", "TestFeature")] {
+            cache.branches.insert(
+                branch.to_string(),
+                BranchState {
+                    failing: vec![test(name)],
+                    selected: Vec::new(),
+                },
+            );
+        }
+        let packages = sample_app().packages;
+        let mut app = App::new(".".into(), packages, cache, RunMode::Failing, false, false);
+        assert_eq!(app.failing_set, HashSet::from([test("TestFeature")]));
+
+        app.failing_set.insert(test("TestNew"));
+        let state = app.cache_state();
+        assert_eq!(state.branches["main"].failing, vec![test("TestMain")]);
+        assert_eq!(state.branches["feature"].failing.len(), 2);
+        assert!(state.failing.is_empty());
+    }
+
+    #[test]
+    fn help_overlay_swallows_keys_until_closed() {
+        let mut app = sample_app();
+        let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+        let press = |app: &mut App, code| {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx)
+        };
+        press(&mut app, KeyCode::Char('?'));
+        assert!(app.help_open);
+        assert!(!press(&mut app, KeyCode::Char('q')));
+        press(&mut app, KeyCode::Char('a'));
+        assert!(runner_rx.try_recv().is_err());
+        app.help_scroll_max.set(5);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.help_scroll, 1);
+        press(&mut app, KeyCode::PageDown);
+        assert_eq!(app.help_scroll, 5);
+        press(&mut app, KeyCode::Esc);
+        assert!(!app.help_open);
+        press(&mut app, KeyCode::Char('?'));
+        press(&mut app, KeyCode::Char('?'));
+        assert!(!app.help_open);
+    }
+
+    #[test]
+    fn loop_mode_reruns_failing_tests_until_green() {
+        let mut app = sample_app();
+        let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+        let test_event = |run_id, action| RunnerEvent::TestEvent {
+            run_id,
+            event: GoTestEvent {
+                action,
+                package: "example".to_string(),
+                test: Some("TestFlaky".to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            },
+        };
+        let run = |app: &mut App, run_id, action| {
+            app.handle_runner_events(vec![
+                RunnerEvent::RunStarted {
+                    run_id,
+                    kind: RunKind::Failing,
+                    packages: 1,
+                },
+                test_event(run_id, action),
+                RunnerEvent::RunFinished {
+                    run_id,
+                    kind: RunKind::Failing,
+                },
+            ]);
+        };
+        app.handle_runner_event(test_event(0, GoTestAction::Fail));
+        app.update_failing_set();
+
+        let key = KeyEvent::new(KeyCode::Char('L'), KeyModifiers::NONE);
+        app.handle_input(Event::Key(key), &runner_tx);
+        assert!(app.loop_until_green);
+        assert!(matches!(runner_rx.try_recv(), Ok(RunnerCommand::Run(_))));
+
+        run(&mut app, 1, GoTestAction::Fail);
+        app.run_due_reruns(&runner_tx);
+        assert!(matches!(runner_rx.try_recv(), Ok(RunnerCommand::Run(_))));
+
+        run(&mut app, 2, GoTestAction::Pass);
+        app.run_due_reruns(&runner_tx);
+        assert!(!app.loop_until_green);
+        assert!(runner_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn follow_mode_focuses_new_failures_with_debounce() {
+        let mut app = sample_app();
+        app.follow_failures = true;
+        app.handle_runner_event(RunnerEvent::RunStarted {
+            run_id: 1,
+            kind: RunKind::All,
+            packages: 1,
+        });
+        let event = |action, name: &str| RunnerEvent::TestEvent {
+            run_id: 1,
+            event: GoTestEvent {
+                action,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            },
+        };
+        app.handle_runner_events(vec![
+            event(GoTestAction::Pass, "TestA"),
+            event(GoTestAction::Pass, "TestB"),
+            event(GoTestAction::Pass, "TestC"),
+        ]);
+        assert_eq!(app.current_test().unwrap().name, "TestA");
+
+        app.handle_runner_event(event(GoTestAction::Fail, "TestC"));
+        assert_eq!(app.current_test().unwrap().name, "TestC");
+        assert!(app.detail_open);
+
+        app.handle_runner_event(event(GoTestAction::Fail, "TestB"));
+        assert_eq!(app.current_test().unwrap().name, "TestC");
+        assert!(app.pending_focus.is_some());
+
+        // No further events arrive; the next tick after the debounce moves
+        // the cursor.
+        app.tick();
+        assert_eq!(app.current_test().unwrap().name, "TestC");
+        app.last_auto_focus = Some(Instant::now() - FOLLOW_FAILURE_DEBOUNCE);
+        app.tick();
+        assert_eq!(app.current_test().unwrap().name, "TestB");
+    }
+
+    #[test]
+    fn groups_spec_by_package() {
+        let app = sample_app();
+        let mut tests = HashSet::new();
+        tests.insert(TestId {
+            package: "example".to_string(),
+            name: "TestFoo".to_string(),
+        });
+        tests.insert(TestId {
+            package: "example".to_string(),
+            name: "TestBar".to_string(),
+        });
+        let spec = app.spec_for_tests(RunKind::Selected, &tests, None).unwrap();
+        assert_eq!(spec.packages.len(), 1);
+        assert_eq!(spec.packages[0].tests.as_ref().unwrap().len(), 2);
+        assert_eq!(spec.packages[0].packages.len(), 1);
+    }
+
+    #[test]
+    fn mark_running_clears_output() {
+        let mut app = sample_app();
+        let id = TestId {
+            package: "example".to_string(),
+            name: "TestFoo".to_string(),
+        };
+        app.registry.ensure_test(&id);
+        if let Some(case) = app.registry.case_mut(&id) {
+            case.output = "old output".to_string();
+            case.raw_output = "old output".to_string();
+            case.status = TestStatus::Failed;
+            case.panic = true;
+        }
+        app.mark_running(&id);
+        let case = app.registry.case(&id).unwrap();
+        assert_eq!(case.status, TestStatus::Running);
+        assert!(case.output.is_empty());
+        assert!(case.raw_output.is_empty());
+        assert!(!case.panic);
+    }
+
+    #[test]
+    fn spinner_advances_only_while_running() {
+        let mut app = sample_app();
+        app.run_state.running = true;
+        app.tick();
+        app.tick();
+        assert_eq!(app.spinner_frame, 2);
+
+        app.run_state.running = false;
+        app.tick();
+        assert_eq!(app.spinner_frame, 0);
+    }
+
+    #[test]
+    fn restores_cursor_per_mode() {
+        let mut app = sample_app();
+        for (name, action) in [
+            ("TestA", GoTestAction::Fail),
+            ("TestB", GoTestAction::Fail),
+            ("TestC", GoTestAction::Pass),
+        ] {
+            app.registry.apply_event(&GoTestEvent {
+                action,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        app.update_failing_set();
+        let select = |app: &mut App, name: &str| {
+            let tests = app.visible_tests();
+            app.selected_test = tests.into_iter().find(|test| test.name == name);
+            app.refresh_lists();
+        };
+
+        select(&mut app, "TestC");
+        app.switch_mode(RunMode::Failing);
+        assert_eq!(app.list_state.selected(), Some(0));
+        let last_failing = app.visible_tests()[1].name.clone();
+        select(&mut app, &last_failing);
+
+        app.switch_mode(RunMode::All);
+        assert_eq!(app.current_test().unwrap().name, "TestC");
+        app.switch_mode(RunMode::Failing);
+        assert_eq!(app.current_test().unwrap().name, last_failing);
+
+        app.failing_set.clear();
+        app.switch_mode(RunMode::All);
+        app.switch_mode(RunMode::Failing);
+        assert_eq!(app.list_state.selected(), None);
+    }
+
+    #[test]
+    fn reruns_whole_package_of_current_test() {
+        let mut app = sample_app();
+        for test in ["example/TestA", "example/TestB", "other/TestC"] {
+            let (package, name) = test.split_once('/').unwrap();
+            app.registry.apply_event(&GoTestEvent {
+                action: GoTestAction::Pass,
+                package: package.to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        let visible = app.visible_tests();
+        app.selected_test = visible.into_iter().find(|test| test.name == "TestA");
+        app.refresh_lists();
+        let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+        let key = KeyEvent::new(KeyCode::Char('P'), KeyModifiers::NONE);
+        app.handle_input(Event::Key(key), &runner_tx);
+
+        let Ok(RunnerCommand::Run(spec)) = runner_rx.try_recv() else {
+            panic!("expected a run");
+        };
+        assert_eq!(spec.kind, RunKind::Selected);
+        assert_eq!(spec.packages.len(), 1);
+        assert_eq!(spec.packages[0].packages, vec!["example".to_string()]);
+        assert!(spec.packages[0].tests.is_none());
+        assert_eq!(app.status_counts().running, 2);
+    }
+
+    #[test]
+    fn searches_output_and_jumps_between_matches() {
+        let mut app = sample_app();
+        let id = TestId {
+            package: "example".to_string(),
+            name: "TestFoo".to_string(),
+        };
+        app.registry.ensure_test(&id);
+        app.registry.case_mut(&id).unwrap().output = "a\nBoom\nb\nc\nboom again\n".to_string();
+        app.refresh_lists();
+        app.detail_open = true;
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let press = |app: &mut App, code| {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx);
+        };
+
+        press(&mut app, KeyCode::Char('/'));
+        for ch in "boom".chars() {
+            press(&mut app, KeyCode::Char(ch));
+        }
+        press(&mut app, KeyCode::Enter);
+        assert!(!app.detail_search_active);
+        assert_eq!(app.detail_matches, vec![1, 4]);
+        assert_eq!(app.detail_scroll, 1);
+
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.detail_scroll, 4);
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.detail_scroll, 1);
+
+        press(&mut app, KeyCode::Char('/'));
+        press(&mut app, KeyCode::Esc);
+        assert!(app.detail_search.is_empty());
+        assert!(app.detail_matches.is_empty());
+    }
+
+    #[test]
+    fn tracks_packages_in_flight() {
+        let mut app = sample_app();
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        app.packages = ["example/a", "example/b", "example/c"]
+            .iter()
+            .map(|package| PackageInfo {
+                import_path: package.to_string(),
+                dir: std::path::PathBuf::from("."),
+            })
+            .collect();
+        app.run_all(&runner_tx);
+        // The default full run is a single `./...` job.
+        app.handle_runner_event(RunnerEvent::RunStarted {
+            run_id: 1,
+            kind: RunKind::All,
+            packages: 1,
+        });
+        app.handle_runner_event(RunnerEvent::PackageStarted {
+            run_id: 1,
+            package: "./...".to_string(),
+        });
+        assert_eq!(app.run_state.packages_total, 3);
+        let event = |action, package: &str, test: Option<&str>| RunnerEvent::TestEvent {
+            run_id: 1,
+            event: GoTestEvent {
+                action,
+                package: package.to_string(),
+                test: test.map(str::to_string),
+                ..Default::default()
+            },
+        };
+        app.handle_runner_event(event(GoTestAction::Start, "example/a", None));
+        app.handle_runner_event(event(GoTestAction::Run, "example/b", Some("TestFoo")));
+        app.handle_runner_event(event(GoTestAction::Pass, "example/a", None));
+        assert_eq!(app.run_state.active_packages, vec!["example/b".to_string()]);
+        assert_eq!(app.run_state.packages_done, 1);
+
+        // A late event of a finished package doesn't restart it.
+        app.handle_runner_event(event(GoTestAction::Output, "example/a", None));
+        assert_eq!(app.run_state.active_packages, vec!["example/b".to_string()]);
+
+        app.handle_runner_event(RunnerEvent::RunFinished {
+            run_id: 1,
+            kind: RunKind::All,
+        });
+        assert!(app.run_state.active_packages.is_empty());
+    }
+
+    #[test]
+    fn mouse_selects_rows_and_double_click_opens_output() {
+        let mut app = sample_app();
+        for name in ["TestA", "TestB", "TestC"] {
+            app.registry.apply_event(&GoTestEvent {
+                action: GoTestAction::Pass,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        app.refresh_lists();
+        app.list_area.set(Rect::new(0, 5, 40, 10));
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let mouse = |app: &mut App, kind, row| {
+            let event = MouseEvent {
+                kind,
+                column: 3,
+                row,
+                modifiers: KeyModifiers::NONE,
+            };
+            app.handle_input(Event::Mouse(event), &runner_tx);
+        };
+        let click = MouseEventKind::Down(MouseButton::Left);
+
+        mouse(&mut app, click, 7);
+        assert_eq!(app.list_state.selected(), Some(1));
+        assert!(!app.detail_open);
+        mouse(&mut app, click, 7);
+        assert!(app.detail_open);
+
+        mouse(&mut app, click, 12);
+        assert_eq!(app.list_state.selected(), Some(1));
+        mouse(&mut app, MouseEventKind::ScrollDown, 0);
+        assert_eq!(app.list_state.selected(), Some(2));
+    }
+
+    #[test]
+    fn reports_run_duration_once_finished() {
+        let mut app = sample_app();
+        app.handle_runner_event(RunnerEvent::RunStarted {
+            run_id: 1,
+            kind: RunKind::All,
+            packages: 0,
+        });
+        assert!(app.run_duration().is_none());
+        app.handle_runner_event(RunnerEvent::RunFinished {
+            run_id: 1,
+            kind: RunKind::All,
+        });
+        assert!(app.run_duration().is_some());
+
+        app.handle_runner_event(RunnerEvent::RunStarted {
+            run_id: 2,
+            kind: RunKind::All,
+            packages: 0,
+        });
+        assert!(app.run_duration().is_none());
+    }
+
+    #[test]
+    fn collapses_subtests_under_their_parent() {
+        let mut app = sample_app();
+        for (name, action) in [
+            ("TestA/one", GoTestAction::Pass),
+            ("TestB", GoTestAction::Pass),
+            ("TestA/two", GoTestAction::Fail),
+        ] {
+            app.registry.apply_event(&GoTestEvent {
+                action,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        let names = |app: &App| -> Vec<String> {
+            app.visible_tests()
+                .into_iter()
+                .map(|test| test.name)
+                .collect()
+        };
+        let parent = TestId {
+            package: "example".to_string(),
+            name: "TestA".to_string(),
+        };
+        assert_eq!(names(&app), vec!["TestA", "TestB"]);
+        assert_eq!(
+            app.row_status(&parent, &app.parent_statuses()),
+            TestStatus::Failed
+        );
+        app.refresh_lists();
+        assert_eq!(app.status_counts().failed, 1);
+
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        app.selected_test = Some(parent);
+        app.refresh_lists();
+        app.handle_input(
+            Event::Key(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE)),
+            &runner_tx,
+        );
+        assert_eq!(
+            names(&app),
+            vec!["TestA", "TestA/two", "TestA/one", "TestB"]
+        );
+    }
+
+    #[test]
+    fn starts_waiting_run_once_packages_are_listed() {
+        let mut app = App::new(
+            std::path::PathBuf::from("."),
+            Vec::new(),
+            CacheState::default(),
+            RunMode::All,
+            true,
+            false,
+        );
+        app.discovering_packages = true;
+        assert!(app.run_all_spec().is_none());
+
+        let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+        app.set_packages(
+            vec![PackageInfo {
+                import_path: "example".to_string(),
+                dir: std::path::PathBuf::from("."),
+            }],
+            &runner_tx,
+        );
+        assert!(!app.discovering_packages);
+        let runs: Vec<RunnerCommand> = runner_rx.try_iter().collect();
+        assert!(matches!(
+            runs.last(),
+            Some(RunnerCommand::Run(spec)) if spec.packages[0].packages == vec!["example".to_string()]
+        ));
+    }
+
+    #[test]
+    fn package_view_summarizes_and_opens_packages() {
+        let mut app = sample_app();
+        for (package, name, action) in [
+            ("example/a", "TestOk", GoTestAction::Pass),
+            ("example/b", "TestOk", GoTestAction::Pass),
+            ("example/b", "TestBad", GoTestAction::Fail),
+        ] {
+            app.registry.apply_event(&GoTestEvent {
+                action,
+                package: package.to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        app.refresh_lists();
+        let summaries = app.package_summaries();
+        assert_eq!(summaries[0].package, "example/b");
+        assert_eq!(summaries[0].status, TestStatus::Failed);
+        let counts = summaries[0].counts;
+        assert_eq!((counts.passed, counts.failed), (1, 1));
+        assert_eq!(summaries[1].status, TestStatus::Passed);
+
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let press = |app: &mut App, code: KeyCode| {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx);
+        };
+        press(&mut app, KeyCode::Char('g'));
+        assert!(app.package_view);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Enter);
+        assert!(!app.package_view);
+        assert_eq!(app.package_focus.as_deref(), Some("example/a"));
+        let visible = app.visible_tests();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].package, "example/a");
+
+        press(&mut app, KeyCode::Char('g'));
+        assert!(app.package_view);
+        assert!(app.package_focus.is_none());
+        assert_eq!(app.package_list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn coalesces_file_changes_during_a_watch_run() {
+        let dir = std::env::temp_dir().join(format!("gest-coalesce-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("b")).unwrap();
+        std::fs::write(dir.join("a.go"), "package a\n").unwrap();
+        std::fs::write(dir.join("b/b.go"), "package b\n").unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let mut app = sample_app();
+        app.watch_enabled = true;
+        app.packages = vec![
+            PackageInfo {
+                import_path: "example/b".to_string(),
+                dir: dir.join("b"),
+            },
+            PackageInfo {
+                import_path: "example".to_string(),
+                dir: dir.clone(),
+            },
+        ];
+        let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+        let changed = |app: &mut App, path: &str| {
+            let event = WatchEvent::FilesChanged(vec![dir.join(path)]);
+            app.handle_watch_event(event, &runner_tx);
+        };
+        let runs = |rx: &crossbeam_channel::Receiver<RunnerCommand>| -> Vec<Vec<String>> {
+            rx.try_iter()
+                .filter_map(|command| match command {
+                    RunnerCommand::Run(spec) => Some(spec.packages[0].packages.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        changed(&mut app, "a.go");
+        changed(&mut app, "b/b.go");
+        changed(&mut app, "a.go");
+        assert_eq!(runs(&runner_rx), vec![vec!["example".to_string()]]);
+
+        app.last_watch_run = Some(Instant::now() - WATCH_COALESCE);
+        app.run_due_reruns(&runner_tx);
+        assert_eq!(
+            runs(&runner_rx),
+            vec![vec!["example".to_string(), "example/b".to_string()]]
+        );
+        app.run_due_reruns(&runner_tx);
+        assert!(runs(&runner_rx).is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn builds_run_command_for_selected_test() {
+        let mut app = sample_app();
+        app.registry.apply_event(&GoTestEvent {
+            action: GoTestAction::Fail,
+            package: "example".to_string(),
+            test: Some("TestFoo/case_1".to_string()),
+            output: None,
+            elapsed: None,
+            ..Default::default()
+        });
+        app.runner_config.go_test_p = 4;
+        app.selected_test = Some(TestId {
+            package: "example".to_string(),
+            name: "TestFoo/case_1".to_string(),
+        });
+        app.expanded.insert(TestId {
+            package: "example".to_string(),
+            name: "TestFoo".to_string(),
+        });
+        app.refresh_lists();
+        assert_eq!(
+            app.current_run_command().unwrap(),
+            "go test -json -p=4 -run '^(TestFoo)$/^(case_1)$' example"
+        );
+    }
+
+    #[test]
+    fn parent_output_lists_subtests_in_emission_order() {
+        let mut app = sample_app();
+        let events = [
+            (GoTestAction::Run, "TestA/one", None),
+            (GoTestAction::Run, "TestA/two", None),
+            (GoTestAction::Output, "TestA/two", Some("two says hi\n")),
+            (GoTestAction::Output, "TestA/one", Some("one says hi\n")),
+        ];
+        for (seq, (action, name, output)) in events.into_iter().enumerate() {
+            app.registry.apply_event(&GoTestEvent {
+                action,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: output.map(str::to_string),
+                elapsed: None,
+                seq: seq as u64 + 1,
+            });
+        }
+        let parent = TestId {
+            package: "example".to_string(),
+            name: "TestA".to_string(),
+        };
+        let text = app.detail_text(&parent).unwrap();
+        let two = text.find("two says hi").unwrap();
+        let one = text.find("one says hi").unwrap();
+        assert!(two < one, "{}", text);
+    }
+
+    #[test]
+    fn sort_order_puts_slowest_tests_first() {
+        let mut app = sample_app();
+        for (action, name, elapsed) in [
+            (GoTestAction::Fail, "TestA", Some(0.1)),
+            (GoTestAction::Pass, "TestB", None),
+            (GoTestAction::Pass, "TestC", Some(2.5)),
+            (GoTestAction::Pass, "TestD", Some(0.7)),
+        ] {
+            app.registry.apply_event(&GoTestEvent {
+                action,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed,
+                ..Default::default()
+            });
+        }
+        let names = |app: &App| -> Vec<String> {
+            let tests = app.visible_tests();
+            tests.into_iter().map(|test| test.name).collect()
+        };
+        assert_eq!(names(&app), vec!["TestA", "TestB", "TestC", "TestD"]);
+
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let key = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
+        app.handle_input(Event::Key(key), &runner_tx);
+        assert_eq!(app.sort_order, SortOrder::Duration);
+        assert_eq!(names(&app), vec!["TestC", "TestD", "TestA", "TestB"]);
+
+        app.handle_input(Event::Key(key), &runner_tx);
+        assert_eq!(app.sort_order, SortOrder::Status);
+    }
+
+    #[test]
+    fn notifies_only_when_a_watch_run_finishes() {
+        let mut app = sample_app();
+        app.notify = true;
+        let run = |app: &mut App, run_id: u64| {
+            app.handle_runner_event(RunnerEvent::RunStarted {
+                run_id,
+                kind: RunKind::All,
+                packages: 1,
+            });
+            app.handle_runner_event(RunnerEvent::TestEvent {
+                run_id,
+                event: GoTestEvent {
+                    action: GoTestAction::Fail,
+                    package: "example".to_string(),
+                    test: Some("TestFoo".to_string()),
+                    output: None,
+                    elapsed: None,
+                    ..Default::default()
+                },
+            });
+            app.handle_runner_event(RunnerEvent::RunFinished {
+                run_id,
+                kind: RunKind::All,
+            });
+        };
+
+        run(&mut app, 1);
+        assert!(app.notification_request.is_none());
+
+        app.watch_run_requested = true;
+        run(&mut app, 2);
+        let (summary, body) = app.notification_request.take().unwrap();
+        assert_eq!(summary, "gest: 1 failed");
+        assert_eq!(body, "0 passed, 1 failed");
+
+        // Tests the view hides, or that this run did not touch, don't count.
+        app.registry.ensure_test(&TestId {
+            package: "example".to_string(),
+            name: "TestOther".to_string(),
+        });
+        app.status_filter = StatusFilter::Passed;
+        app.refresh_lists();
+        app.watch_run_requested = true;
+        run(&mut app, 3);
+        let (_, body) = app.notification_request.take().unwrap();
+        assert_eq!(body, "0 passed, 1 failed");
+    }
+
+    #[test]
+    fn rerun_last_resends_the_previous_run() {
+        let mut app = sample_app();
+        for name in ["TestA", "TestB"] {
+            app.registry.apply_event(&GoTestEvent {
+                action: GoTestAction::Pass,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        app.refresh_lists();
+        let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+        let press = |app: &mut App, code| {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx);
+        };
+        let run_tests = |rx: &crossbeam_channel::Receiver<RunnerCommand>| {
+            rx.try_iter()
+                .find_map(|command| match command {
+                    RunnerCommand::Run(spec) => Some(spec.packages[0].tests.clone()),
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        press(&mut app, KeyCode::Char('.'));
+        assert_eq!(app.last_error(), Some("nothing has run yet"));
+
+        press(&mut app, KeyCode::Char('r'));
+        let first = run_tests(&runner_rx);
+        assert_eq!(first, Some(vec!["TestA".to_string()]));
+
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Char('o'));
+        press(&mut app, KeyCode::Char('.'));
+        assert_eq!(run_tests(&runner_rx), first);
+    }
+
+    #[test]
+    fn error_log_keeps_recent_errors() {
+        let mut app = sample_app();
+        for index in 0..ERROR_LOG_LIMIT + 2 {
+            app.report_error(format!("error {}", index));
+        }
+        assert_eq!(app.errors.len(), ERROR_LOG_LIMIT);
+        assert_eq!(app.errors.front().unwrap().message, "error 2");
+        let newest = format!("error {}", ERROR_LOG_LIMIT + 1);
+        assert_eq!(app.last_error(), Some(newest.as_str()));
+
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let press = |app: &mut App, code| {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx);
+        };
+        press(&mut app, KeyCode::Char('E'));
+        assert!(app.error_log_open);
+        press(&mut app, KeyCode::Char('a'));
+        assert!(app.error_log_open);
+        press(&mut app, KeyCode::Esc);
+        assert!(!app.error_log_open);
+    }
+
+    #[test]
+    fn select_mode_marks_and_unmarks_all_matches() {
+        let mut app = sample_app();
+        for name in ["TestIntegrationA", "TestIntegrationB", "TestUnit"] {
+            app.registry.apply_event(&GoTestEvent {
+                action: GoTestAction::Pass,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        app.mode = RunMode::Selecting;
+        app.selection.query = "Integration".to_string();
+        app.refresh_selection_filter();
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let ctrl = |app: &mut App, ch| {
+            let key = KeyEvent::new(KeyCode::Char(ch), KeyModifiers::CONTROL);
+            app.handle_input(Event::Key(key), &runner_tx);
+        };
+
+        ctrl(&mut app, 'a');
+        let mut selected: Vec<String> = app
+            .selected_set
+            .iter()
+            .map(|test| test.name.clone())
+            .collect();
+        selected.sort();
+        assert_eq!(selected, vec!["TestIntegrationA", "TestIntegrationB"]);
+        assert_eq!(app.selection.query, "Integration");
+
+        ctrl(&mut app, 'u');
+        assert!(app.selected_set.is_empty());
+    }
+
+    #[test]
+    fn fuzzy_filter_records_matched_positions() {
+        let mut app = sample_app();
+        app.registry.apply_event(&GoTestEvent {
+            action: GoTestAction::Run,
+            package: "example".to_string(),
+            test: Some("TestAlpha".to_string()),
+            output: None,
+            elapsed: None,
+            ..Default::default()
+        });
+        let id = TestId {
+            package: "example".to_string(),
+            name: "TestAlpha".to_string(),
+        };
+        app.selection.query = "alp".to_string();
+        app.refresh_selection_filter();
+        assert_eq!(app.selection.positions.get(&id), Some(&vec![4, 5, 6]));
+
+        app.selection.query.clear();
+        app.refresh_selection_filter();
+        assert!(app.selection.positions.is_empty());
+    }
+
+    #[test]
+    fn run_pattern_scopes_all_runs_and_seeds_the_query() {
+        let mut app = sample_app();
+        app.set_run_pattern("TestAuth".to_string());
+        assert_eq!(app.selection.query, "TestAuth");
+        let spec = app.run_all_spec().unwrap();
+        assert_eq!(spec.run_pattern.as_deref(), Some("TestAuth"));
+        assert_eq!(spec.packages[0].tests, None);
+
+        // A regex would only confuse the fuzzy query.
+        let mut app = sample_app();
+        app.set_run_pattern("^TestAuth(Login|Logout)$".to_string());
+        assert!(app.selection.query.is_empty());
+        let spec = app.run_all_spec().unwrap();
+        assert_eq!(
+            spec.run_pattern.as_deref(),
+            Some("^TestAuth(Login|Logout)$")
+        );
+    }
+
+    #[test]
+    fn diffs_output_against_the_previous_run() {
+        let mut app = sample_app();
+        let id = TestId {
+            package: "example".to_string(),
+            name: "TestFlaky".to_string(),
+        };
+        let event = |action, output: Option<&str>| GoTestEvent {
+            action,
+            package: "example".to_string(),
+            test: Some("TestFlaky".to_string()),
+            output: output.map(str::to_string),
+            elapsed: None,
+            ..Default::default()
+        };
+        let run = |app: &mut App, seed: &str, result| {
+            app.mark_running(&id);
+            app.registry.apply_event(&event(GoTestAction::Run, None));
+            for line in [seed, "ok\n"] {
+                app.registry
+                    .apply_event(&event(GoTestAction::Output, Some(line)));
+            }
+            app.registry.apply_event(&event(result, None));
+        };
+        run(&mut app, "seed 1\n", GoTestAction::Pass);
+        run(&mut app, "seed 2\n", GoTestAction::Fail);
+        assert_eq!(app.registry.case(&id).unwrap().prev_output, "seed 1\nok\n");
+
+        app.detail_diff = true;
+        assert_eq!(
+            app.detail_text(&id).unwrap(),
+            "--- previous run\n+++ this run\n- seed 1\n+ seed 2\n  ok\n"
+        );
+    }
+
+    #[test]
+    fn shows_the_cache_file_path_until_the_next_key() {
+        let mut app = sample_app();
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let press = |app: &mut App, code| {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx);
+        };
+        press(&mut app, KeyCode::Char('I'));
+        let expected = format!("cache file: {}", cache_file(&app.repo_root).display());
+        assert_eq!(app.info.as_deref(), Some(expected.as_str()));
+        press(&mut app, KeyCode::Down);
+        assert!(app.info.is_none());
+    }
+
+    #[test]
+    fn tails_running_output_until_scrolled_up() {
+        let mut app = sample_app();
+        app.registry.apply_event(&GoTestEvent {
+            action: GoTestAction::Run,
+            package: "example".to_string(),
+            test: Some("TestSlow".to_string()),
+            output: None,
+            elapsed: None,
+            ..Default::default()
+        });
+        app.refresh_lists();
+        app.detail_open = true;
+        app.detail_focused = true;
+        app.detail_scroll_max.set(20);
+        assert!(app.tailing());
+
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let press = |app: &mut App, code| {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx);
+        };
+        press(&mut app, KeyCode::Up);
+        assert!(!app.tailing());
+        assert_eq!(app.detail_scroll, 19);
+
+        press(&mut app, KeyCode::Char('t'));
+        assert!(app.tailing());
+        press(&mut app, KeyCode::Char('t'));
+        assert!(!app.tail);
+    }
+
+    #[test]
+    fn saves_and_loads_selection_presets() {
+        let mut app = sample_app();
+        let id = |name: &str| TestId {
+            package: "example".to_string(),
+            name: name.to_string(),
+        };
+        app.selected_set = [id("TestLogin"), id("TestLogout")].into_iter().collect();
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let type_keys = |app: &mut App, keys: &str| {
+            for ch in keys.chars() {
+                let code = if ch == '\n' {
+                    KeyCode::Enter
+                } else {
+                    KeyCode::Char(ch)
+                };
+                let key = KeyEvent::new(code, KeyModifiers::NONE);
+                app.handle_input(Event::Key(key), &runner_tx);
+            }
+        };
+
+        type_keys(&mut app, "Sauth\n");
+        assert!(app.preset_prompt.is_none());
+        assert_eq!(app.presets["auth"], vec![id("TestLogin"), id("TestLogout")]);
+
+        app.selected_set.clear();
+        type_keys(&mut app, "Gauth\n");
+        assert_eq!(app.selected_set.len(), 2);
+        assert!(app.selected_set.contains(&id("TestLogin")));
+
+        let state = app.cache_state();
+        let reloaded = App::new(
+            std::path::PathBuf::from("."),
+            Vec::new(),
+            state,
+            RunMode::All,
+            false,
+            false,
+        );
+        assert_eq!(reloaded.presets.len(), 1);
+    }
+
+    #[test]
+    fn marks_silent_running_tests_as_stuck() {
+        let mut app = sample_app();
+        app.stuck_after = Some(Duration::from_secs(30));
+        app.run_state.running = true;
+        for name in ["TestHang", "TestBusy"] {
+            app.registry.apply_event(&GoTestEvent {
+                action: GoTestAction::Run,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        let hang = TestId {
+            package: "example".to_string(),
+            name: "TestHang".to_string(),
+        };
+        let case = app.registry.case_mut(&hang).unwrap();
+        case.last_update = Some(Instant::now() - Duration::from_secs(31));
+
+        app.tick();
+        assert_eq!(app.stuck, HashSet::from([hang.clone()]));
+
+        // Paused until the serial tests finish, not stuck.
+        app.registry.apply_event(&GoTestEvent {
+            action: GoTestAction::Pause,
+            package: "example".to_string(),
+            test: Some("TestHang".to_string()),
+            ..Default::default()
+        });
+        app.registry.case_mut(&hang).unwrap().last_update =
+            Some(Instant::now() - Duration::from_secs(31));
+        app.tick();
+        assert!(app.stuck.is_empty());
+
+        app.run_state.running = false;
+        app.tick();
+        assert!(app.stuck.is_empty());
+    }
+
+    #[test]
+    fn esc_cancels_the_current_run() {
+        let mut app = sample_app();
+        let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        app.handle_input(Event::Key(esc), &runner_tx);
+        assert!(runner_rx.try_recv().is_err());
+
+        app.handle_runner_event(RunnerEvent::RunStarted {
+            run_id: 4,
+            kind: RunKind::All,
+            packages: 1,
+        });
+        app.handle_input(Event::Key(esc), &runner_tx);
+        assert!(matches!(
+            runner_rx.try_recv(),
+            Ok(RunnerCommand::Cancel { run_id: Some(4) })
+        ));
+        assert!(app.run_state.cancelled);
+
+        app.handle_runner_event(RunnerEvent::RunError {
+            run_id: 4,
+            message: CANCEL_MESSAGE.to_string(),
+        });
+        app.handle_runner_event(RunnerEvent::RunFinished {
+            run_id: 4,
+            kind: RunKind::All,
+        });
+        assert!(!app.run_state.running);
+        assert!(app.run_state.cancelled);
+        assert!(app.errors.is_empty());
+    }
+
+    #[test]
+    fn keeps_a_capped_result_history_across_sessions() {
+        let mut app = sample_app();
+        let flaky = TestId {
+            package: "example".to_string(),
+            name: "TestFlaky".to_string(),
+        };
+        for run_id in 1..=HISTORY_LIMIT as u64 + 2 {
+            app.handle_runner_event(RunnerEvent::RunStarted {
+                run_id,
+                kind: RunKind::All,
+                packages: 1,
+            });
+            let action = if run_id == 3 {
+                GoTestAction::Fail
+            } else {
+                GoTestAction::Pass
+            };
+            app.handle_runner_event(RunnerEvent::TestEvent {
+                run_id,
+                event: GoTestEvent {
+                    action,
+                    package: "example".to_string(),
+                    test: Some(flaky.name.clone()),
+                    output: None,
+                    elapsed: None,
+                    ..Default::default()
+                },
+            });
+            app.handle_runner_event(RunnerEvent::RunFinished {
+                run_id,
+                kind: RunKind::All,
+            });
+        }
+
+        let results = &app.history[&flaky];
+        assert_eq!(results.len(), HISTORY_LIMIT);
+        assert_eq!(results.iter().filter(|&&passed| !passed).count(), 1);
+        assert!(!results[0]);
+
+        let reloaded = App::new(
+            std::path::PathBuf::from("."),
+            Vec::new(),
+            app.cache_state(),
+            RunMode::All,
+            false,
+            false,
+        );
+        assert_eq!(reloaded.history[&flaky], *results);
+    }
+
+    #[test]
+    fn pkg_prefix_filters_the_select_list_by_package() {
+        let mut app = sample_app();
+        for (package, name) in [
+            ("example.com/api", "TestLogin"),
+            ("example.com/store", "TestLogin"),
+            ("example.com/store", "TestSave"),
+        ] {
+            app.registry.ensure_test(&TestId {
+                package: package.to_string(),
+                name: name.to_string(),
+            });
+        }
+
+        app.selection.query = "pkg:Store login".to_string();
+        app.refresh_selection_filter();
+        assert_eq!(
+            app.selection.filtered,
+            vec![TestId {
+                package: "example.com/store".to_string(),
+                name: "TestLogin".to_string(),
+            }]
+        );
+
+        app.selection.query = "pkg:store".to_string();
+        app.refresh_selection_filter();
+        assert_eq!(app.selection.filtered.len(), 2);
+    }
+
+    #[test]
+    fn q_during_a_run_needs_confirmation() {
+        let mut app = sample_app();
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let press = |app: &mut App, code| {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx)
+        };
+        app.run_state.running = true;
+
+        assert!(!press(&mut app, KeyCode::Char('q')));
+        assert!(app.quit_pending());
+        press(&mut app, KeyCode::Down);
+        assert!(!app.quit_pending());
+
+        assert!(!press(&mut app, KeyCode::Char('q')));
+        assert!(press(&mut app, KeyCode::Char('q')));
+
+        app.run_state.running = false;
+        assert!(press(&mut app, KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn rerunning_a_parent_marks_its_subtests_running() {
+        let mut app = sample_app();
+        for (name, action) in [
+            ("TestA/one", GoTestAction::Pass),
+            ("TestA/two", GoTestAction::Fail),
+            ("TestB", GoTestAction::Pass),
+        ] {
+            app.registry.apply_event(&GoTestEvent {
+                action,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        let parent = TestId {
+            package: "example".to_string(),
+            name: "TestA".to_string(),
+        };
+        app.selected_test = Some(parent.clone());
+        app.refresh_lists();
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        app.handle_input(
+            Event::Key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE)),
+            &runner_tx,
+        );
+
+        let status = |name: &str| {
+            let id = TestId {
+                package: "example".to_string(),
+                name: name.to_string(),
+            };
+            app.registry.case(&id).unwrap().status
+        };
+        assert_eq!(status("TestA/one"), TestStatus::Running);
+        assert_eq!(status("TestA/two"), TestStatus::Running);
+        assert_eq!(status("TestB"), TestStatus::Passed);
+        assert_eq!(
+            app.row_status(&parent, &app.parent_statuses()),
+            TestStatus::Running
+        );
+    }
+
+    #[test]
+    fn run_timeout_applies_to_every_run() {
+        let mut app = sample_app();
+        app.runner_config.run_timeout = Some(Duration::from_secs(30));
+        let test = TestId {
+            package: "example".to_string(),
+            name: "TestFoo".to_string(),
+        };
+        app.registry.ensure_test(&test);
+
+        let tests: HashSet<TestId> = [test].into_iter().collect();
+        let spec = app.spec_for_tests(RunKind::Selected, &tests, None).unwrap();
+        assert_eq!(spec.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(
+            app.run_all_spec().unwrap().timeout,
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn watch_coalesce_window_is_configurable() {
+        let mut app = sample_app();
+        app.last_watch_run = Some(Instant::now());
+        assert!(!app.watch_rerun_due());
+        app.watch_coalesce = Duration::ZERO;
+        assert!(app.watch_rerun_due());
+    }
+
+    #[test]
+    fn history_skips_partial_and_cached_runs_and_drops_removed_tests() {
+        let mut app = sample_app();
+        let test = |name: &str| TestId {
+            package: "example".to_string(),
+            name: name.to_string(),
+        };
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let run = |app: &mut App,
+                   run_id: u64,
+                   events: &[(GoTestAction, Option<&str>, &str)],
+                   cancel: bool| {
+            app.handle_runner_event(RunnerEvent::RunStarted {
+                run_id,
+                kind: RunKind::All,
+                packages: 1,
+            });
+            for (action, name, output) in events {
+                app.handle_runner_event(RunnerEvent::TestEvent {
+                    run_id,
+                    event: GoTestEvent {
+                        action: action.clone(),
+                        package: "example".to_string(),
+                        test: name.map(str::to_string),
+                        output: Some(output.to_string()).filter(|output| !output.is_empty()),
+                        elapsed: name.is_none().then_some(0.1),
+                        ..Default::default()
+                    },
+                });
+            }
+            if cancel {
+                app.cancel_run(&runner_tx);
+            }
+            app.handle_runner_event(RunnerEvent::RunFinished {
+                run_id,
+                kind: RunKind::All,
+            });
+        };
+
+        run(
+            &mut app,
+            1,
+            &[
+                (GoTestAction::Pass, Some("TestKeep"), ""),
+                (GoTestAction::Pass, Some("TestGone"), ""),
+                (GoTestAction::Pass, None, ""),
+            ],
+            false,
+        );
+        let first = app.cache_state();
+        let mut app = App::new(
+            std::path::PathBuf::from("."),
+            Vec::new(),
+            app.cache_state(),
+            RunMode::All,
+            false,
+            false,
+        );
+        assert_eq!(app.history[&test("TestGone")], vec![true]);
+
+        // Cancelled: partial, not recorded.
+        run(
+            &mut app,
+            2,
+            &[(GoTestAction::Fail, Some("TestKeep"), "")],
+            true,
+        );
+        assert_eq!(app.history[&test("TestKeep")], vec![true]);
+
+        // Cached: a repeat of run 1, not recorded.
+        run(
+            &mut app,
+            3,
+            &[
+                (GoTestAction::Run, Some("TestKeep"), ""),
+                (GoTestAction::Pass, Some("TestKeep"), ""),
+                (GoTestAction::Output, None, "ok  \texample\t(cached)\n"),
+                (GoTestAction::Pass, None, ""),
+            ],
+            false,
+        );
+        assert_eq!(app.history[&test("TestKeep")], vec![true]);
+
+        // TestGone was not reported by a full run of its package.
+        let saved = app.cache_state().history;
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].test, test("TestKeep"));
+
+        // A --run pattern only ran TestKeep; TestGone may still exist.
+        let mut app = App::new(
+            std::path::PathBuf::from("."),
+            Vec::new(),
+            first,
+            RunMode::All,
+            false,
+            false,
+        );
+        app.set_run_pattern("TestKeep".to_string());
+        run(
+            &mut app,
+            4,
+            &[
+                (GoTestAction::Pass, Some("TestKeep"), ""),
+                (GoTestAction::Pass, None, ""),
+            ],
+            false,
+        );
+        assert_eq!(app.cache_state().history.len(), 2);
+    }
+
+    #[test]
+    fn shortens_packages_to_unambiguous_suffixes() {
+        let mut app = sample_app();
+        let packages = [
+            "example.com/svc/v1/api",
+            "example.com/svc/v2/api",
+            "example.com/svc/store",
+        ];
+        app.packages = packages
+            .iter()
+            .map(|package| PackageInfo {
+                import_path: package.to_string(),
+                dir: std::path::PathBuf::from("."),
+            })
+            .collect();
+        app.refresh_lists();
+        assert_eq!(app.package_label("example.com/svc/v1/api", false), "v1/api");
+        assert_eq!(app.package_label("example.com/svc/v2/api", false), "v2/api");
+        assert_eq!(app.package_label("example.com/svc/store", false), "store");
+        assert_eq!(
+            app.package_label("example.com/svc/store", true),
+            "example.com/svc/store"
+        );
+
+        // A new package that clashes with an existing label lengthens it.
+        app.registry.ensure_test(&TestId {
+            package: "example.com/other/store".to_string(),
+            name: "TestFoo".to_string(),
+        });
+        app.refresh_lists();
+        assert_eq!(
+            app.package_label("example.com/svc/store", false),
+            "svc/store"
+        );
+    }
+
+    #[test]
+    fn replay_disables_run_keys() {
+        let mut app = sample_app();
+        app.replaying = true;
+        app.handle_runner_event(RunnerEvent::RunStarted {
+            run_id: REPLAY_RUN_ID,
+            kind: RunKind::All,
+            packages: 1,
+        });
+        app.handle_runner_event(RunnerEvent::TestEvent {
+            run_id: REPLAY_RUN_ID,
+            event: GoTestEvent {
+                action: GoTestAction::Fail,
+                package: "example".to_string(),
+                test: Some("TestFoo".to_string()),
+                ..Default::default()
+            },
+        });
+        let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+        for code in [KeyCode::Char('r'), KeyCode::Char('a'), KeyCode::Esc] {
+            app.handle_input(
+                Event::Key(KeyEvent::new(code, KeyModifiers::NONE)),
+                &runner_tx,
+            );
+        }
+        assert!(runner_rx.try_recv().is_err());
+        assert_eq!(
+            app.info.as_deref(),
+            Some("runs are disabled during a replay")
+        );
+        assert!(app.run_state.running);
+        let test = app.current_test().unwrap();
+        assert_eq!(app.registry.case(&test).unwrap().status, TestStatus::Failed);
+    }
+
+    #[test]
+    fn listed_packages_start_the_waiting_run_only_in_all_mode() {
+        let package = PackageInfo {
+            import_path: "example".to_string(),
+            dir: std::path::PathBuf::from("."),
+        };
+        let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+        let mut app = sample_app();
+        app.packages.clear();
+        app.package_filter_active = true;
+        app.discovering_packages = true;
+        app.mode = RunMode::Failing;
+        app.set_packages(vec![package.clone()], &runner_tx);
+        assert!(runner_rx.try_iter().next().is_none());
+
+        app.packages.clear();
+        app.discovering_packages = true;
+        app.mode = RunMode::All;
+        app.last_run = sample_app().run_all_spec();
+        app.set_packages(vec![package], &runner_tx);
+        assert!(runner_rx.try_iter().next().is_none());
+    }
+
+    #[test]
+    fn search_matches_follow_the_selected_test_and_new_output() {
+        let mut app = sample_app();
+        let first = TestId {
+            package: "example".to_string(),
+            name: "TestA".to_string(),
+        };
+        let second = TestId {
+            package: "example".to_string(),
+            name: "TestB".to_string(),
+        };
+        app.registry.ensure_test(&first);
+        app.registry.ensure_test(&second);
+        app.registry.case_mut(&first).unwrap().output = "boom\n".to_string();
+        app.registry.case_mut(&second).unwrap().output = "ok\nboom\nboom\n".to_string();
+        app.refresh_lists();
+        app.detail_open = true;
+        app.detail_search = "boom".to_string();
+        app.select_test(&first);
+        app.refresh_lists();
+        assert_eq!(app.detail_matches, vec![0]);
+
+        app.select_test(&second);
+        app.refresh_lists();
+        assert_eq!(app.detail_matches, vec![1, 2]);
+        assert_eq!(app.detail_match, None);
+
+        app.handle_runner_event(RunnerEvent::TestEvent {
+            run_id: 0,
+            event: GoTestEvent {
+                action: GoTestAction::Output,
+                package: "example".to_string(),
+                test: Some("TestB".to_string()),
+                output: Some("boom once more\n".to_string()),
+                ..Default::default()
+            },
+        });
+        assert_eq!(app.detail_matches, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn muting_and_filtering_move_the_cursor_off_hidden_tests() {
+        let mut app = sample_app();
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        for (package, action) in [
+            ("example", GoTestAction::Fail),
+            ("example/noisy", GoTestAction::Pass),
+        ] {
+            app.registry.apply_event(&GoTestEvent {
+                action,
+                package: package.to_string(),
+                test: Some("TestFoo".to_string()),
+                ..Default::default()
+            });
+        }
+        let noisy = TestId {
+            package: "example/noisy".to_string(),
+            name: "TestFoo".to_string(),
+        };
+        app.refresh_lists();
+        app.select_test(&noisy);
+        let press = |app: &mut App, ch| {
+            let key = KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx);
+        };
+
+        press(&mut app, 'm');
+        assert_eq!(app.selected_test.as_ref().unwrap().package, "example");
+        press(&mut app, 'M');
+        app.select_test(&noisy);
+
+        // The first filter shows only failed tests, which hides the passed one.
+        press(&mut app, 'f');
+        assert_eq!(app.status_filter, StatusFilter::Failed);
+        assert_eq!(app.selected_test.as_ref().unwrap().package, "example");
+    }
+
+    #[test]
+    fn failfast_leaves_no_test_running() {
+        let mut app = sample_app();
+        app.handle_runner_event(RunnerEvent::RunStarted {
+            run_id: 1,
+            kind: RunKind::All,
+            packages: 1,
+        });
+        for (package, action) in [
+            ("example", GoTestAction::Fail),
+            ("example/b", GoTestAction::Run),
+        ] {
+            app.handle_runner_event(RunnerEvent::TestEvent {
+                run_id: 1,
+                event: GoTestEvent {
+                    action,
+                    package: package.to_string(),
+                    test: Some("TestFoo".to_string()),
+                    ..Default::default()
+                },
+            });
+        }
+        app.handle_runner_event(RunnerEvent::FailFast {
+            run_id: 1,
+            package: "./...".to_string(),
+        });
+        app.handle_runner_event(RunnerEvent::PackageFinished {
+            run_id: 1,
+            package: "./...".to_string(),
+            success: false,
+        });
+        app.handle_runner_event(RunnerEvent::RunFinished {
+            run_id: 1,
+            kind: RunKind::All,
+        });
+
+        assert_eq!(app.registry.running_tests().count(), 0);
+        assert_eq!(app.status_counts().running, 0);
+        assert_eq!(app.status_counts().failed, 1);
+    }
+
+    #[test]
+    fn m_toggles_the_mute_of_the_highlighted_package() {
+        let mut app = sample_app();
+        for package in ["example/a", "example/b"] {
+            app.registry.apply_event(&GoTestEvent {
+                action: GoTestAction::Pass,
+                package: package.to_string(),
+                test: Some("TestFoo".to_string()),
+                ..Default::default()
+            });
+        }
+        app.refresh_lists();
+        let (runner_tx, _runner_rx) = crossbeam_channel::unbounded();
+        let press = |app: &mut App, code: KeyCode| {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            app.handle_input(Event::Key(key), &runner_tx);
+        };
+        // The test list cursor is on example/a; the package view highlights b.
+        press(&mut app, KeyCode::Char('g'));
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Char('m'));
+        assert_eq!(app.muted_packages, HashSet::from(["example/b".to_string()]));
+        let summaries = app.package_summaries();
+        assert_eq!(summaries.last().unwrap().package, "example/b");
+        assert!(summaries.last().unwrap().muted);
+
+        // Still listed, last, so the same key unmutes it.
+        press(&mut app, KeyCode::Char('m'));
+        assert!(app.muted_packages.is_empty());
+
+        press(&mut app, KeyCode::Char('g'));
+        press(&mut app, KeyCode::Char('m'));
+        assert_eq!(app.muted_packages.len(), 1);
+    }
+
+    #[test]
+    fn fixed_build_failures_leave_no_entry_behind() {
+        let mut app = sample_app();
+        let build = TestId {
+            package: "example".to_string(),
+            name: PACKAGE_BUILD_TEST.to_string(),
+        };
+        let run = |app: &mut App, run_id: u64, kind: RunKind, events: Vec<GoTestEvent>| {
+            app.handle_runner_event(RunnerEvent::RunStarted {
+                run_id,
+                kind,
+                packages: 1,
+            });
+            for event in events {
+                app.handle_runner_event(RunnerEvent::TestEvent { run_id, event });
+            }
+            app.handle_runner_event(RunnerEvent::RunFinished { run_id, kind });
+        };
+        let package_event = |action, output: Option<&str>| GoTestEvent {
+            action,
+            package: "example".to_string(),
+            output: output.map(str::to_string),
+            ..Default::default()
+        };
+        run(
+            &mut app,
+            1,
+            RunKind::All,
+            vec![
+                package_event(GoTestAction::BuildOutput, Some("# example\n")),
+                package_event(GoTestAction::Fail, None),
+            ],
+        );
+        assert!(app.failing_set.contains(&build));
+
+        // A failing-tests run, which doesn't rebuild the failing set.
+        run(
+            &mut app,
+            2,
+            RunKind::Failing,
+            vec![
+                GoTestEvent {
+                    action: GoTestAction::Pass,
+                    package: "example".to_string(),
+                    test: Some("TestFoo".to_string()),
+                    ..Default::default()
+                },
+                package_event(GoTestAction::Pass, None),
+            ],
+        );
+        assert!(app.registry.case(&build).is_none());
+        assert!(!app.failing_set.contains(&build));
+        app.refresh_lists();
+        assert_eq!(app.status_counts().passed, 1);
+        assert!(!app.rows().contains(&build));
     }
 }
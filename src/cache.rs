@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -18,12 +19,49 @@ pub struct PackageCache {
     packages: Vec<PackageInfo>,
 }
 
+impl PackageCache {
+    pub fn generated_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.generated_at)
+    }
+}
+
+/// Failing and selected tests remembered for one git branch.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BranchState {
+    pub failing: Vec<TestId>,
+    pub selected: Vec<TestId>,
+}
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct CacheState {
+    /// Used when not on a git branch (no repo or detached `HEAD`).
     pub failing: Vec<TestId>,
     pub selected: Vec<TestId>,
     #[serde(default)]
+    pub branches: HashMap<String, BranchState>,
+    /// Branch checked out when gest started; picks the entry in `branches`.
+    #[serde(skip)]
+    pub branch: Option<String>,
+    #[serde(default)]
     pub package_cache: Option<PackageCache>,
+    #[serde(default)]
+    pub muted_packages: Vec<String>,
+    /// Build tags the cached results were produced with.
+    #[serde(default)]
+    pub tags: Option<String>,
+    /// Named selections saved with `S` and loaded with `G`.
+    #[serde(default)]
+    pub presets: HashMap<String, Vec<TestId>>,
+    /// Recent pass/fail results per test, across sessions.
+    #[serde(default)]
+    pub history: Vec<TestHistory>,
+}
+
+/// A test's latest results, oldest first (`true` for a pass).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestHistory {
+    pub test: TestId,
+    pub results: Vec<bool>,
 }
 
 pub fn load_cache(path: &Path) -> Result<CacheState, RunnerError> {
@@ -43,6 +81,22 @@ pub fn save_cache(path: &Path, state: &CacheState) -> Result<(), RunnerError> {
     Ok(())
 }
 
+/// Drops results recorded under a different `-tags` configuration, since the
+/// same test can behave differently (or not exist) with other build tags.
+/// Returns `true` when anything was reset.
+pub fn reset_for_build_tags(state: &mut CacheState, tags: Option<&str>) -> bool {
+    if state.tags.as_deref() == tags {
+        return false;
+    }
+    state.failing.clear();
+    for branch in state.branches.values_mut() {
+        branch.failing.clear();
+    }
+    state.package_cache = None;
+    state.tags = tags.map(str::to_string);
+    true
+}
+
 pub fn cached_packages(root: &Path, state: &CacheState) -> Option<Vec<PackageInfo>> {
     let cache = state.package_cache.as_ref()?;
     let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
@@ -90,3 +144,29 @@ fn mtimes_match(cached: Option<u64>, current: Option<u64>) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resets_failures_when_build_tags_change() {
+        let test = TestId {
+            package: "example".to_string(),
+            name: "TestFoo".to_string(),
+        };
+        let mut state = CacheState {
+            failing: vec![test.clone()],
+            selected: vec![test],
+            ..Default::default()
+        };
+
+        assert!(!reset_for_build_tags(&mut state, None));
+        assert_eq!(state.failing.len(), 1);
+
+        assert!(reset_for_build_tags(&mut state, Some("integration")));
+        assert!(state.failing.is_empty());
+        assert_eq!(state.selected.len(), 1);
+        assert_eq!(state.tags.as_deref(), Some("integration"));
+    }
+}
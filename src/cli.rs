@@ -1,27 +1,288 @@
+use std::fmt;
+use std::str::FromStr;
+
 use clap::{Parser, ValueEnum};
+use serde::Deserialize;
 
 #[derive(Parser, Debug)]
 #[command(name = "gest", version, about = "Jest-like Go test runner")]
 pub struct Cli {
     #[arg(long, value_enum, default_value = "all")]
     pub mode: ModeArg,
-    #[arg(long, default_value_t = num_cpus::get())]
-    pub pkg_concurrency: usize,
+    #[arg(long, visible_alias = "concurrency", default_value_t = Concurrency::Fixed(num_cpus::get()))]
+    pub pkg_concurrency: Concurrency,
     #[arg(long)]
     pub sequential: bool,
+    /// Packages gest runs at once, overriding `--pkg-concurrency` for the
+    /// worker count only.
+    #[arg(long, value_name = "N", conflicts_with = "sequential")]
+    pub pkg_workers: Option<usize>,
+    /// `go test -p` for each run, overriding `--pkg-concurrency` for go's
+    /// parallelism only.
+    #[arg(long, value_name = "N", conflicts_with = "sequential")]
+    pub go_p: Option<usize>,
     #[arg(long)]
     pub no_watch: bool,
+    /// Wait this long after the last file change before rerunning.
+    #[arg(long, value_name = "MILLIS", default_value_t = 250)]
+    pub watch_debounce: u64,
+    /// Merge file changes arriving this soon after a watch-triggered run
+    /// started into one follow-up run.
+    #[arg(long, value_name = "MILLIS", default_value_t = 1000)]
+    pub watch_coalesce: u64,
+    /// Only watch these directories for changes (repeatable) instead of the
+    /// whole repo.
+    #[arg(long, value_name = "DIR")]
+    pub watch_paths: Vec<std::path::PathBuf>,
+    /// Run all tests once without the TUI, print failures and exit non-zero
+    /// if any failed.
+    #[arg(long, visible_alias = "ci")]
+    pub no_tui: bool,
     #[arg(long)]
     pub no_test_cache: bool,
+    /// Only test packages whose import path matches this regex; repeat to
+    /// keep packages matching any of them.
+    #[arg(long, value_name = "REGEX")]
+    pub packages: Vec<String>,
+    /// Leave out packages whose import path matches this regex.
+    #[arg(long, value_name = "REGEX")]
+    pub exclude_packages: Option<String>,
+    /// Only run tests matching this regex (passed to `go test -run`); also
+    /// starts the select-mode query with it.
+    #[arg(long = "run", value_name = "REGEX")]
+    pub run_pattern: Option<String>,
+    /// Run `go test all` (the module plus all of its dependencies) instead of `./...`.
+    #[arg(long, conflicts_with_all = ["packages", "exclude_packages"])]
+    pub test_all: bool,
+    /// Build tags forwarded to `go test -tags` and `go list -tags`.
+    #[arg(long, visible_alias = "build-tags", value_name = "TAGS")]
+    pub tags: Option<String>,
+    /// Cancel any run that takes longer than this many seconds (0 means no
+    /// limit).
+    #[arg(long, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+    /// Mark a running test `STUCK` after this many seconds without any
+    /// event for it (0 turns this off).
+    #[arg(long, value_name = "SECONDS", default_value_t = 60)]
+    pub stuck_after: u64,
+    /// Enable the race detector (`go test -race`); roughly doubles runtime.
+    #[arg(long)]
+    pub race: bool,
+    /// Run benchmarks matching this pattern (`go test -bench`) instead of tests.
+    #[arg(long, value_name = "PATTERN")]
+    pub bench: Option<String>,
+    /// Report per-package statement coverage (`go test -cover`).
+    #[arg(long)]
+    pub cover: bool,
+    /// Set an environment variable for test runs (repeatable).
+    #[arg(long = "env", value_name = "KEY=VALUE", value_parser = parse_env_var)]
+    pub env: Vec<(String, String)>,
+    /// Retry failing tests of single-test and selected reruns up to N times.
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub retries: u32,
+    /// Stop the run after the first failing test (`go test -failfast`).
     #[arg(long)]
-    pub packages: Option<String>,
+    pub failfast: bool,
+    /// Run test binaries through this wrapper via `go test -exec`.
+    #[arg(long, value_name = "WRAPPER")]
+    pub exec: Option<String>,
+    /// Run this command instead of `go test` (shell-split; must emit `go test -json` output).
+    #[arg(long, value_name = "CMD", value_parser = parse_test_command)]
+    pub test_command: Option<TestCommand>,
+    /// Print the `go test` commands for the initial run and exit.
+    #[arg(long)]
+    pub dry_run: bool,
     #[arg(long)]
     pub debug: bool,
+    /// Show the output pane right of the test list instead of below it.
+    #[arg(long)]
+    pub detail_right: bool,
+    /// Send a desktop notification when a watch-triggered run finishes.
+    #[arg(long)]
+    pub notify: bool,
+    /// Serve newline-delimited JSON commands on this unix socket instead of
+    /// starting the TUI, streaming runner events back to editor clients.
+    #[arg(long, value_name = "PATH", conflicts_with = "no_tui")]
+    pub control_socket: Option<std::path::PathBuf>,
+    /// Append every raw line of `go test -json` output to this file.
+    #[arg(long, value_name = "PATH")]
+    pub dump: Option<std::path::PathBuf>,
+    /// Play back a saved `go test -json` stream (e.g. from `--dump`) in the
+    /// UI instead of running tests.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["no_tui", "control_socket", "dry_run"])]
+    pub replay: Option<std::path::PathBuf>,
+    /// Pause between replayed lines.
+    #[arg(long, value_name = "MILLIS", default_value_t = 5, requires = "replay")]
+    pub replay_delay: u64,
+    /// Write a TAP report of the final results to this path on exit (`-` for stdout).
+    #[arg(long, value_name = "PATH")]
+    pub tap: Option<std::path::PathBuf>,
+    /// Write a JUnit XML report of the final results to this path on exit (`-` for stdout).
+    #[arg(long, value_name = "PATH")]
+    pub junit: Option<std::path::PathBuf>,
+    /// Write every test's status, duration and output as JSON to this path on exit (`-` for stdout).
+    #[arg(long, value_name = "PATH")]
+    pub export_json: Option<std::path::PathBuf>,
+    /// Stop capturing a test's output past this many bytes (0 for no limit).
+    #[arg(long, value_name = "BYTES", default_value_t = 1024 * 1024)]
+    pub max_output_bytes: usize,
+    /// Case matching for the select-mode fuzzy query.
+    #[arg(long, value_enum, default_value = "smart")]
+    pub fuzzy_case: FuzzyCaseArg,
+    /// Fuzzy-match only test names, ignoring the package path.
+    #[arg(long)]
+    pub fuzzy_name_only: bool,
+    /// Extra `go test` arguments, given after `--` (e.g. `-- -shuffle=on`).
+    #[arg(last = true, value_name = "GO_TEST_ARGS")]
+    pub go_test_args: Vec<String>,
 }
 
-#[derive(ValueEnum, Debug, Clone, Copy)]
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ModeArg {
     All,
     Failing,
     Select,
 }
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum FuzzyCaseArg {
+    Smart,
+    Respect,
+    Ignore,
+}
+
+/// Package worker count: either a fixed number or `auto`, which is resolved
+/// against the discovered package count.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Concurrency {
+    Auto,
+    Fixed(usize),
+}
+
+impl Concurrency {
+    /// Returns `(pkg_concurrency, go_test_p)` for the given number of packages.
+    ///
+    /// `auto` never spawns more workers than there are packages. When there
+    /// are at most half as many packages as CPUs, it doubles `go test -p`
+    /// instead, so the few workers keep the whole machine busy.
+    pub fn resolve(self, cpus: usize, package_count: usize) -> (usize, usize) {
+        match self {
+            Concurrency::Fixed(count) => (count.max(1), count.max(1)),
+            Concurrency::Auto => {
+                let cpus = cpus.max(1);
+                let few = package_count > 0 && package_count * 2 <= cpus;
+                let go_test_p = if few { cpus * 2 } else { cpus };
+                (cpus.min(package_count).max(1), go_test_p)
+            }
+        }
+    }
+}
+
+impl FromStr for Concurrency {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("auto") {
+            return Ok(Concurrency::Auto);
+        }
+        value
+            .parse::<usize>()
+            .map(Concurrency::Fixed)
+            .map_err(|_| format!("expected a number or `auto`, got `{}`", value))
+    }
+}
+
+impl fmt::Display for Concurrency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Concurrency::Auto => write!(f, "auto"),
+            Concurrency::Fixed(count) => write!(f, "{}", count),
+        }
+    }
+}
+
+/// A user-supplied replacement for `go test`, already split into program and arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCommand(pub Vec<String>);
+
+fn parse_test_command(value: &str) -> Result<TestCommand, String> {
+    let words = shell_words::split(value).map_err(|err| err.to_string())?;
+    if words.is_empty() {
+        return Err("test command is empty".to_string());
+    }
+    Ok(TestCommand(words))
+}
+
+fn parse_env_var(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{}'", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_repeated_env_vars() {
+        let args = ["gest", "--env", "CGO_ENABLED=0", "--env", "A=b=c"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!(
+            cli.env,
+            vec![
+                ("CGO_ENABLED".to_string(), "0".to_string()),
+                ("A".to_string(), "b=c".to_string())
+            ]
+        );
+        assert!(Cli::try_parse_from(["gest", "--env", "NOVALUE"]).is_err());
+        assert!(Cli::try_parse_from(["gest", "--env", "=x"]).is_err());
+    }
+
+    #[test]
+    fn splits_test_command_like_a_shell() {
+        let args = ["gest", "--test-command", "gotestsum -f 'standard verbose' --"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!(
+            cli.test_command.unwrap().0,
+            vec!["gotestsum", "-f", "standard verbose", "--"]
+        );
+        assert!(Cli::try_parse_from(["gest", "--test-command", " "]).is_err());
+        assert!(Cli::try_parse_from(["gest", "--test-command", "'unterminated"]).is_err());
+    }
+
+    #[test]
+    fn collects_go_test_args_after_double_dash() {
+        let args = ["gest", "--race", "--", "-shuffle=on", "-vet=off"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert!(cli.race);
+        assert_eq!(cli.go_test_args, vec!["-shuffle=on", "-vet=off"]);
+    }
+
+    #[test]
+    fn parses_concurrency_values() {
+        assert_eq!("auto".parse::<Concurrency>(), Ok(Concurrency::Auto));
+        assert_eq!("4".parse::<Concurrency>(), Ok(Concurrency::Fixed(4)));
+        assert!("many".parse::<Concurrency>().is_err());
+    }
+
+    #[test]
+    fn auto_concurrency_caps_workers_at_package_count() {
+        assert_eq!(Concurrency::Auto.resolve(8, 3), (3, 16));
+        assert_eq!(Concurrency::Auto.resolve(8, 4), (4, 16));
+        assert_eq!(Concurrency::Auto.resolve(8, 5), (5, 8));
+        assert_eq!(Concurrency::Auto.resolve(8, 40), (8, 8));
+        assert_eq!(Concurrency::Auto.resolve(8, 0), (1, 8));
+        assert_eq!(Concurrency::Fixed(2).resolve(8, 40), (2, 2));
+    }
+
+    #[test]
+    fn parses_separate_worker_and_go_parallelism() {
+        let args = ["gest", "--pkg-workers", "4", "--go-p", "1"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!((cli.pkg_workers, cli.go_p), (Some(4), Some(1)));
+        let args = ["gest", "--go-p", "1", "--sequential"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+}
@@ -0,0 +1,203 @@
+//! `.gest.toml` at the repo root: checked-in defaults for CLI flags.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use clap::parser::ValueSource;
+use clap::{Arg, ArgMatches, CommandFactory};
+use serde::{Deserialize, Deserializer};
+
+use crate::cli::{Cli, Concurrency, ModeArg};
+use crate::runner::RunnerError;
+
+pub const CONFIG_FILE: &str = ".gest.toml";
+
+/// Flag defaults from `.gest.toml`. Keys are the long flag names with `_`
+/// instead of `-`; flags given on the command line win.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub mode: Option<ModeArg>,
+    #[serde(default, deserialize_with = "concurrency")]
+    pub pkg_concurrency: Option<Concurrency>,
+    pub sequential: Option<bool>,
+    pub no_watch: Option<bool>,
+    pub watch_debounce: Option<u64>,
+    pub watch_coalesce: Option<u64>,
+    pub no_test_cache: Option<bool>,
+    pub packages: Option<Vec<String>>,
+    pub exclude_packages: Option<String>,
+    pub tags: Option<String>,
+    pub timeout: Option<u64>,
+    pub stuck_after: Option<u64>,
+    pub race: Option<bool>,
+    pub cover: Option<bool>,
+    pub env: Option<BTreeMap<String, String>>,
+    pub retries: Option<u32>,
+    pub failfast: Option<bool>,
+    pub exec: Option<String>,
+    pub detail_right: Option<bool>,
+    pub notify: Option<bool>,
+    pub max_output_bytes: Option<usize>,
+    pub go_test_args: Option<Vec<String>>,
+}
+
+/// Reads `.gest.toml` from `root`, or `None` when there is none.
+pub fn load_config(root: &Path) -> Result<Option<Config>, RunnerError> {
+    let path = root.join(CONFIG_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = fs::read_to_string(&path).map_err(|err| RunnerError::Io(err.to_string()))?;
+    parse_config(&data)
+        .map(Some)
+        .map_err(|err| RunnerError::Parse(format!("{}: {}", path.display(), err)))
+}
+
+pub fn parse_config(data: &str) -> Result<Config, String> {
+    toml::from_str(data).map_err(|err| err.to_string())
+}
+
+impl Config {
+    /// Fills in every flag from the file that `matches` did not get on the
+    /// command line. A file value that conflicts with a flag given on the
+    /// command line (e.g. `packages` with `--test-all`) is dropped, since
+    /// clap only checks conflicts between flags.
+    pub fn apply(self, cli: &mut Cli, matches: &ArgMatches) {
+        let command = Cli::command();
+        let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+        let conflicts = |arg: &Arg, id: &str| {
+            command
+                .get_arg_conflicts_with(arg)
+                .iter()
+                .any(|other| other.get_id() == id)
+        };
+        let conflicted = |id: &str| {
+            let Some(own) = command.get_arguments().find(|arg| arg.get_id() == id) else {
+                return false;
+            };
+            command
+                .get_arguments()
+                .filter(|arg| given(arg.get_id().as_str()))
+                .any(|arg| conflicts(arg, id) || conflicts(own, arg.get_id().as_str()))
+        };
+        let unset = |id: &str| !given(id) && !conflicted(id);
+        macro_rules! fill {
+            ($($field:ident),*) => {
+                $(if let Some(value) = self.$field {
+                    if unset(stringify!($field)) {
+                        cli.$field = value.into();
+                    }
+                })*
+            };
+        }
+        fill!(
+            mode,
+            pkg_concurrency,
+            sequential,
+            no_watch,
+            watch_debounce,
+            watch_coalesce,
+            no_test_cache,
+            packages,
+            exclude_packages,
+            tags,
+            timeout,
+            stuck_after,
+            race,
+            cover,
+            retries,
+            failfast,
+            exec,
+            detail_right,
+            notify,
+            max_output_bytes,
+            go_test_args
+        );
+        if let Some(env) = self.env {
+            if unset("env") {
+                cli.env = env.into_iter().collect();
+            }
+        }
+    }
+}
+
+/// `pkg_concurrency` takes a number or `"auto"`, like the flag.
+fn concurrency<'de, D>(deserializer: D) -> Result<Option<Concurrency>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Count(usize),
+        Text(String),
+    }
+
+    match Option::<Value>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Value::Count(count)) => Ok(Some(Concurrency::Fixed(count))),
+        Some(Value::Text(text)) => text.parse().map(Some).map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, FromArgMatches};
+
+    #[test]
+    fn command_line_flags_override_config_values() {
+        let config = parse_config(
+            r#"
+            pkg_concurrency = "auto"
+            no_test_cache = true
+            packages = ["^example/api"]
+            tags = "integration"
+            timeout = 300
+
+            [env]
+            CGO_ENABLED = "0"
+            "#,
+        )
+        .unwrap();
+        let matches = Cli::command()
+            .try_get_matches_from(["gest", "--tags", "e2e"])
+            .unwrap();
+        let mut cli = Cli::from_arg_matches(&matches).unwrap();
+        config.apply(&mut cli, &matches);
+
+        assert_eq!(cli.pkg_concurrency, Concurrency::Auto);
+        assert!(cli.no_test_cache);
+        assert_eq!(cli.packages, vec!["^example/api".to_string()]);
+        assert_eq!(cli.tags.as_deref(), Some("e2e"));
+        assert_eq!(cli.timeout, Some(300));
+        assert_eq!(cli.env, vec![("CGO_ENABLED".to_string(), "0".to_string())]);
+
+        assert!(parse_config("pkg_concurency = 4").is_err());
+    }
+
+    #[test]
+    fn command_line_flags_drop_conflicting_config_values() {
+        let config = parse_config(
+            r#"
+            packages = ["^example/api"]
+            sequential = true
+            tags = "integration"
+            "#,
+        )
+        .unwrap();
+        let matches = Cli::command()
+            .try_get_matches_from(["gest", "--test-all", "--pkg-workers", "4"])
+            .unwrap();
+        let mut cli = Cli::from_arg_matches(&matches).unwrap();
+        config.apply(&mut cli, &matches);
+
+        assert!(cli.test_all);
+        assert!(cli.packages.is_empty());
+        assert_eq!(cli.pkg_workers, Some(4));
+        assert!(!cli.sequential);
+        assert_eq!(cli.tags.as_deref(), Some("integration"));
+    }
+}
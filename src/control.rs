@@ -0,0 +1,318 @@
+//! Newline-delimited JSON control channel for editor integrations
+//! (`--control-socket`): clients send commands, gest streams runner events
+//! back to every connected client.
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crossbeam_channel::{Receiver, Sender};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::app::App;
+use crate::go::GoTestAction;
+use crate::model::TestId;
+use crate::runner::{RunKind, RunnerCommand, RunnerEvent};
+
+/// How long a write to one client may block before that client is dropped,
+/// so a client that stops reading can't stall the event loop.
+pub const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// A command from a control client, one JSON object per line, e.g.
+/// `{"command":"run","tests":[{"package":"example","name":"TestFoo"}]}`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// Runs `tests`, or every test when none are given.
+    Run {
+        #[serde(default)]
+        tests: Vec<TestId>,
+    },
+    /// Cancels the current run.
+    Cancel,
+    /// Replaces the selected set with `tests`.
+    Select { tests: Vec<TestId> },
+}
+
+pub fn parse_command(line: &str) -> Result<ControlCommand, String> {
+    serde_json::from_str(line).map_err(|err| err.to_string())
+}
+
+/// Connected clients; every message is written to all of them.
+#[derive(Clone, Default)]
+pub struct ControlClients(Arc<Mutex<Vec<Box<dyn Write + Send>>>>);
+
+impl ControlClients {
+    pub fn add(&self, client: Box<dyn Write + Send>) {
+        if let Ok(mut clients) = self.0.lock() {
+            clients.push(client);
+        }
+    }
+
+    /// Writes `message` as one line to every client, dropping the ones that
+    /// went away or timed out.
+    pub fn broadcast(&self, message: &Value) {
+        let line = format!("{}\n", message);
+        if let Ok(mut clients) = self.0.lock() {
+            clients.retain_mut(|client| client.write_all(line.as_bytes()).is_ok());
+        }
+    }
+}
+
+/// Listens on a unix socket at `path` and forwards each command line (or
+/// the reason it could not be parsed) to `tx`. A stale socket left at `path`
+/// by an earlier session is replaced; any other file is an error.
+#[cfg(unix)]
+pub fn start_control_server(
+    path: &std::path::Path,
+    tx: crossbeam_channel::Sender<Result<ControlCommand, String>>,
+) -> std::io::Result<ControlClients> {
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixListener;
+
+    if std::fs::metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    let clients = ControlClients::default();
+    let accepted = clients.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().map_while(Result::ok) {
+            if let Ok(writer) = stream.try_clone() {
+                if writer.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT)).is_ok() {
+                    accepted.add(Box::new(writer));
+                }
+            }
+            let tx = tx.clone();
+            std::thread::spawn(move || {
+                for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    if tx.send(parse_command(&line)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    Ok(clients)
+}
+
+/// Runs the headless control loop: executes client commands against `app`
+/// and streams runner events, plus a `summary` after each run, to every
+/// client until `shutdown` fires or a channel closes.
+pub fn serve_control(
+    app: &mut App,
+    clients: &ControlClients,
+    commands: &Receiver<Result<ControlCommand, String>>,
+    runner_tx: &Sender<RunnerCommand>,
+    runner_events: &Receiver<RunnerEvent>,
+    shutdown: &Receiver<()>,
+) {
+    loop {
+        crossbeam_channel::select! {
+            recv(commands) -> command => match command {
+                Ok(Ok(ControlCommand::Run { tests })) => {
+                    let started = if tests.is_empty() {
+                        let runnable = app.run_all_spec().is_some();
+                        app.run_all(runner_tx);
+                        runnable
+                    } else {
+                        app.run_tests(&tests.into_iter().collect(), runner_tx)
+                    };
+                    if !started {
+                        clients.broadcast(&json!({"event": "error", "message": "nothing to run"}));
+                    }
+                }
+                Ok(Ok(ControlCommand::Cancel)) => app.cancel_run(runner_tx),
+                Ok(Ok(ControlCommand::Select { tests })) => {
+                    app.set_selected(tests.into_iter().collect());
+                    clients.broadcast(&json!({
+                        "event": "selected",
+                        "tests": app.selected_set.len(),
+                    }));
+                }
+                Ok(Err(message)) => {
+                    clients.broadcast(&json!({"event": "error", "message": message}));
+                }
+                Err(_) => break,
+            },
+            recv(runner_events) -> event => {
+                let Ok(event) = event else { break };
+                clients.broadcast(&event_json(&event));
+                let finished = matches!(event, RunnerEvent::RunFinished { .. });
+                app.handle_runner_events(vec![event]);
+                if finished && !app.run_state.running {
+                    let counts = app.status_counts();
+                    clients.broadcast(&json!({
+                        "event": "summary",
+                        "passed": counts.passed,
+                        "failed": counts.failed,
+                        "skipped": counts.skipped,
+                        "failed_tests": app.registry.failed_tests(),
+                    }));
+                }
+            }
+            recv(shutdown) -> _ => break,
+        }
+    }
+}
+
+/// The JSON line sent to clients for a runner event.
+pub fn event_json(event: &RunnerEvent) -> Value {
+    match event {
+        RunnerEvent::RunStarted {
+            run_id,
+            kind,
+            packages,
+        } => json!({
+            "event": "run_started",
+            "run_id": run_id,
+            "kind": kind_name(*kind),
+            "packages": packages,
+        }),
+        RunnerEvent::PackageStarted { run_id, package } => json!({
+            "event": "package_started",
+            "run_id": run_id,
+            "package": package,
+        }),
+        RunnerEvent::TestEvent { run_id, event } => json!({
+            "event": "test",
+            "run_id": run_id,
+            "action": action_name(&event.action),
+            "package": event.package,
+            "test": event.test,
+            "output": event.output,
+            "elapsed": event.elapsed,
+        }),
+        RunnerEvent::PackageFinished {
+            run_id,
+            package,
+            success,
+        } => json!({
+            "event": "package_finished",
+            "run_id": run_id,
+            "package": package,
+            "success": success,
+        }),
+        RunnerEvent::RunFinished { run_id, kind } => json!({
+            "event": "run_finished",
+            "run_id": run_id,
+            "kind": kind_name(*kind),
+        }),
+        RunnerEvent::RunError { run_id, message } => json!({
+            "event": "run_error",
+            "run_id": run_id,
+            "message": message,
+        }),
+        RunnerEvent::Retry {
+            run_id,
+            package,
+            tests,
+            attempt,
+            max,
+        } => json!({
+            "event": "retry",
+            "run_id": run_id,
+            "package": package,
+            "tests": tests,
+            "attempt": attempt,
+            "max": max,
+        }),
+        RunnerEvent::FailFast { run_id, package } => json!({
+            "event": "fail_fast",
+            "run_id": run_id,
+            "package": package,
+        }),
+        RunnerEvent::UnparsedLine { run_id, line } => json!({
+            "event": "unparsed_line",
+            "run_id": run_id,
+            "line": line,
+        }),
+    }
+}
+
+fn kind_name(kind: RunKind) -> &'static str {
+    match kind {
+        RunKind::All => "all",
+        RunKind::Failing => "failing",
+        RunKind::Selected => "selected",
+        RunKind::Single => "single",
+    }
+}
+
+/// Go's own name for the action, as in `go test -json`.
+fn action_name(action: &GoTestAction) -> &'static str {
+    match action {
+        GoTestAction::Start => "start",
+        GoTestAction::Run => "run",
+        GoTestAction::Pass => "pass",
+        GoTestAction::Fail => "fail",
+        GoTestAction::Skip => "skip",
+        GoTestAction::Output => "output",
+        GoTestAction::Pause => "pause",
+        GoTestAction::Cont => "cont",
+        GoTestAction::BuildOutput => "build-output",
+        GoTestAction::Other => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_control_commands() {
+        let run = r#"{"command":"run","tests":[{"package":"example","name":"TestFoo"}]}"#;
+        assert_eq!(
+            parse_command(run),
+            Ok(ControlCommand::Run {
+                tests: vec![TestId {
+                    package: "example".to_string(),
+                    name: "TestFoo".to_string(),
+                }],
+            })
+        );
+        assert_eq!(
+            parse_command(r#"{"command":"run"}"#),
+            Ok(ControlCommand::Run { tests: Vec::new() })
+        );
+        assert_eq!(
+            parse_command(r#"{"command":"cancel"}"#),
+            Ok(ControlCommand::Cancel)
+        );
+        assert!(parse_command(r#"{"command":"explode"}"#).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn serves_commands_and_streams_events_over_a_socket() {
+        use std::io::{BufRead, BufReader};
+        use std::os::unix::net::UnixStream;
+
+        let path = std::env::temp_dir().join(format!("gest-control-{}.sock", std::process::id()));
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let clients = start_control_server(&path, tx).unwrap();
+        let mut stream = UnixStream::connect(&path).unwrap();
+        stream.write_all(b"{\"command\":\"cancel\"}\n").unwrap();
+        let timeout = std::time::Duration::from_secs(5);
+        assert_eq!(rx.recv_timeout(timeout), Ok(Ok(ControlCommand::Cancel)));
+
+        clients.broadcast(&event_json(&RunnerEvent::RunFinished {
+            run_id: 3,
+            kind: RunKind::All,
+        }));
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line).unwrap();
+        let value: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(
+            value,
+            json!({"event": "run_finished", "run_id": 3, "kind": "all"})
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
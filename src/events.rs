@@ -2,14 +2,27 @@ use std::path::PathBuf;
 
 use crossterm::event::Event;
 
+use crate::cache::PackageCache;
+use crate::repo::PackageInfo;
+
 #[derive(Debug)]
 pub enum AppEvent {
     Input(Event),
     Watch(WatchEvent),
     Tick,
+    /// Packages listed in the background at startup, or the `go list` error.
+    Packages(Result<PackageListing, String>),
     Shutdown,
 }
 
+#[derive(Debug)]
+pub struct PackageListing {
+    /// Packages left after `--packages` filtering.
+    pub packages: Vec<PackageInfo>,
+    /// Cache entry covering every listed package.
+    pub cache: Option<PackageCache>,
+}
+
 #[derive(Debug)]
 pub enum WatchEvent {
     FilesChanged(Vec<PathBuf>),
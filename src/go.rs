@@ -1,57 +1,121 @@
-use serde::Deserialize;
+use serde_json::{Map, Value};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum GoTestAction {
+    /// A package's test binary started (go 1.20+).
+    Start,
     Run,
     Pass,
     Fail,
     Skip,
     Output,
+    /// A parallel test yielded (`t.Parallel`) and will `Cont`inue later.
+    Pause,
+    Cont,
+    /// Compiler output for a package (`build-output`, and stderr from older
+    /// go versions that don't report builds in the JSON stream).
+    BuildOutput,
+    #[default]
     Other,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct GoTestEvent {
     pub action: GoTestAction,
     pub package: String,
     pub test: Option<String>,
     pub output: Option<String>,
     pub elapsed: Option<f64>,
+    /// Order in which the runner received the event within its run, so
+    /// output can be put back in emission order. 0 when not stamped.
+    pub seq: u64,
 }
 
-#[derive(Deserialize)]
-struct RawGoTestEvent {
-    #[serde(rename = "Action")]
-    action: String,
-    #[serde(rename = "Package")]
-    package: Option<String>,
-    #[serde(rename = "Test")]
-    test: Option<String>,
-    #[serde(rename = "Output")]
-    output: Option<String>,
-    #[serde(rename = "Elapsed")]
-    elapsed: Option<f64>,
-}
-
+/// Parses one line of `go test -json` output.
+///
+/// Any valid JSON yields an event so format drift never silently drops a
+/// line: unknown actions and unexpected shapes become `GoTestAction::Other`,
+/// and fields with an unexpected type are treated as absent. Only lines that
+/// are not JSON at all return `None`.
 pub fn parse_go_test_line(line: &str) -> Option<GoTestEvent> {
-    let raw: RawGoTestEvent = serde_json::from_str(line).ok()?;
-    let action = match raw.action.as_str() {
-        "run" => GoTestAction::Run,
-        "pass" => GoTestAction::Pass,
-        "fail" => GoTestAction::Fail,
-        "skip" => GoTestAction::Skip,
-        "output" => GoTestAction::Output,
+    let value: Value = serde_json::from_str(line).ok()?;
+    let Some(fields) = value.as_object() else {
+        return Some(GoTestEvent {
+            action: GoTestAction::Other,
+            package: String::new(),
+            test: None,
+            output: None,
+            elapsed: None,
+            seq: 0,
+        });
+    };
+    let action = match fields.get("Action").and_then(Value::as_str) {
+        Some("run") => GoTestAction::Run,
+        Some("pass") => GoTestAction::Pass,
+        Some("fail") => GoTestAction::Fail,
+        Some("skip") => GoTestAction::Skip,
+        Some("output") => GoTestAction::Output,
+        Some("start") => GoTestAction::Start,
+        Some("pause") => GoTestAction::Pause,
+        Some("cont") => GoTestAction::Cont,
+        Some("build-output") => GoTestAction::BuildOutput,
         _ => GoTestAction::Other,
     };
+    // Build events carry `ImportPath` (e.g. `example [example.test]`)
+    // instead of `Package`.
+    let package = string_field(fields, "Package")
+        .or_else(|| string_field(fields, "ImportPath").map(|path| build_package(&path).to_string()))
+        .unwrap_or_default();
     Some(GoTestEvent {
         action,
-        package: raw.package.unwrap_or_default(),
-        test: raw.test,
-        output: raw.output,
-        elapsed: raw.elapsed,
+        package,
+        test: string_field(fields, "Test"),
+        output: string_field(fields, "Output"),
+        elapsed: fields.get("Elapsed").and_then(Value::as_f64),
+        seq: 0,
     })
 }
 
+/// Strips the ` [example.test]` variant suffix from a build import path or a
+/// `# example [example.test]` compiler header.
+pub fn build_package(import_path: &str) -> &str {
+    let path = import_path.trim().trim_start_matches("# ");
+    path.split_once(" [").map_or(path, |(package, _)| package)
+}
+
+/// Returns the benchmark name from a result line such as
+/// `BenchmarkFoo-8   1000000   1053 ns/op`. `go test` appends `-<procs>` to
+/// the last name element unless `procs` (GOMAXPROCS) is 1; only that suffix
+/// is stripped, so `BenchmarkFoo/size-1024` keeps its own.
+pub fn benchmark_name(line: &str, procs: usize) -> Option<&str> {
+    let mut fields = line.split_whitespace();
+    let name = fields.next()?;
+    let iterations = fields.next()?;
+    if !name.starts_with("Benchmark")
+        || !iterations.chars().all(|ch| ch.is_ascii_digit())
+        || !fields.any(|field| field.ends_with("/op"))
+    {
+        return None;
+    }
+    if procs <= 1 {
+        return Some(name);
+    }
+    Some(name.strip_suffix(&format!("-{}", procs)).unwrap_or(name))
+}
+
+/// The GOMAXPROCS `go test` runs with: `$GOMAXPROCS`, else the CPU count.
+pub fn gomaxprocs() -> usize {
+    std::env::var("GOMAXPROCS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|&procs| procs > 0)
+        .unwrap_or_else(num_cpus::get)
+}
+
+fn string_field(fields: &Map<String, Value>, key: &str) -> Option<String> {
+    fields.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,6 +129,37 @@ mod tests {
         assert_eq!(event.test.as_deref(), Some("TestFoo"));
     }
 
+    #[test]
+    fn recognizes_benchmark_result_lines() {
+        assert_eq!(
+            benchmark_name("BenchmarkFoo-8   \t 1000000\t      1053 ns/op\n", 8),
+            Some("BenchmarkFoo")
+        );
+        assert_eq!(
+            benchmark_name("BenchmarkFoo/size-10-4 \t 50\t 20 ns/op\t 8 B/op\n", 4),
+            Some("BenchmarkFoo/size-10")
+        );
+        assert_eq!(
+            benchmark_name("BenchmarkFoo/size-1024 \t 50\t 20 ns/op\n", 1),
+            Some("BenchmarkFoo/size-1024")
+        );
+        assert_eq!(
+            benchmark_name("BenchmarkFoo/size-1024 \t 50\t 20 ns/op\n", 8),
+            Some("BenchmarkFoo/size-1024")
+        );
+        assert_eq!(benchmark_name("BenchmarkFoo-8\n", 8), None);
+        assert_eq!(benchmark_name("TestFoo-8 1 1 ns/op\n", 8), None);
+    }
+
+    #[test]
+    fn parses_build_output_event() {
+        let line = r#"{"ImportPath":"example [example.test]","Action":"build-output","Output":"./foo.go:3:1: syntax error\n"}"#;
+        let event = parse_go_test_line(line).unwrap();
+        assert_eq!(event.action, GoTestAction::BuildOutput);
+        assert_eq!(event.package, "example");
+        assert_eq!(build_package("# example/sub"), "example/sub");
+    }
+
     #[test]
     fn parses_output_event() {
         let line = r#"{"Action":"output","Package":"example","Test":"TestFoo","Output":"panic: boom\n"}"#;
@@ -72,4 +167,36 @@ mod tests {
         assert_eq!(event.action, GoTestAction::Output);
         assert_eq!(event.output.as_deref(), Some("panic: boom\n"));
     }
+
+    #[test]
+    fn parses_pause_and_cont_events() {
+        let line = r#"{"Action":"pause","Package":"example","Test":"TestFoo"}"#;
+        let event = parse_go_test_line(line).unwrap();
+        assert_eq!(event.action, GoTestAction::Pause);
+        let line = r#"{"Action":"cont","Package":"example","Test":"TestFoo"}"#;
+        let event = parse_go_test_line(line).unwrap();
+        assert_eq!(event.action, GoTestAction::Cont);
+    }
+
+    #[test]
+    fn classifies_unexpected_json_as_other() {
+        let line = r#"{"Action":"frobnicate","Package":"example","Elapsed":"soon"}"#;
+        let event = parse_go_test_line(line).unwrap();
+        assert_eq!(event.action, GoTestAction::Other);
+        assert_eq!(event.package, "example");
+        assert_eq!(event.elapsed, None);
+
+        let line = r#"{"Action":{"nested":"run"},"Test":["TestFoo"]}"#;
+        let event = parse_go_test_line(line).unwrap();
+        assert_eq!(event.action, GoTestAction::Other);
+        assert_eq!(event.test, None);
+
+        let event = parse_go_test_line("[1, 2, 3]").unwrap();
+        assert_eq!(event.action, GoTestAction::Other);
+    }
+
+    #[test]
+    fn rejects_non_json_lines() {
+        assert!(parse_go_test_line("# example").is_none());
+    }
 }
@@ -1,10 +1,13 @@
 pub mod app;
 pub mod cache;
 pub mod cli;
+pub mod config;
+pub mod control;
 pub mod events;
 pub mod go;
 pub mod model;
 pub mod repo;
+pub mod report;
 pub mod runner;
 pub mod ui;
 pub mod watcher;
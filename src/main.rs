@@ -1,8 +1,11 @@
 use std::collections::VecDeque;
+use std::fs::File;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
-use clap::Parser;
-use crossterm::event::{self};
+use clap::{CommandFactory, FromArgMatches};
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture};
 use crossterm::execute;
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
@@ -10,59 +13,191 @@ use crossterm::terminal::{
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 
-use gest::app::{App, RunMode};
-use gest::cache::{cached_packages, load_cache, save_cache, update_package_cache};
-use gest::cli::{Cli, ModeArg};
-use gest::events::AppEvent;
-use gest::repo::{cache_file, ensure_cache_dir, filter_packages, find_repo_root, list_packages};
-use gest::runner::{start_runner, RunnerCommand, RunnerConfig};
+use gest::app::{App, FuzzyCase, FuzzyOptions, RunMode};
+use gest::cache::{
+    cached_packages, load_cache, reset_for_build_tags, save_cache, update_package_cache,
+    CacheState, PackageCache,
+};
+use gest::cli::{Cli, FuzzyCaseArg, ModeArg};
+use gest::config::load_config;
+#[cfg(unix)]
+use gest::control::{serve_control, start_control_server};
+use gest::events::{AppEvent, PackageListing};
+use gest::go::gomaxprocs;
+use gest::repo::{
+    cache_file, check_go_toolchain, current_branch, ensure_cache_dir, filter_packages,
+    find_repo_root, list_packages, package_patterns,
+};
+use gest::report::{json_report, junit_report, tap_report};
+use gest::runner::{
+    command_lines, start_replay, start_runner, RunnerCommand, RunnerConfig, RunnerEvent,
+};
 use gest::ui;
 use gest::watcher::start_watcher;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
     let cwd = std::env::current_dir()?;
-    let repo_root = find_repo_root(&cwd).ok_or("No go.mod found in this directory or parents")?;
-    ensure_cache_dir(&repo_root)?;
+    // A replay needs no Go module; it only reads the saved stream.
+    let repo_root = match cli.replay {
+        Some(_) => cwd.clone(),
+        None => {
+            find_repo_root(&cwd).ok_or("No go.mod or go.work found in this directory or parents")?
+        }
+    };
+    if let Some(config) = load_config(&repo_root)? {
+        config.apply(&mut cli, &matches);
+    }
+    let watch_paths: Vec<_> = cli.watch_paths.iter().map(|path| cwd.join(path)).collect();
+    if let Some(path) = watch_paths.iter().find(|path| !path.is_dir()) {
+        return Err(format!("--watch-paths: {} is not a directory", path.display()).into());
+    }
+    if cli.replay.is_none() {
+        if let Err(err) = check_go_toolchain() {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+    }
     let cache_path = cache_file(&repo_root);
-    let mut cache = load_cache(&cache_path).unwrap_or_default();
+    // Replays leave the cache alone, so they don't create `.gest/` either.
+    let mut cache = if cli.replay.is_none() {
+        ensure_cache_dir(&repo_root)?;
+        load_cache(&cache_path).unwrap_or_default()
+    } else {
+        CacheState::default()
+    };
+    let tags_changed = reset_for_build_tags(&mut cache, cli.tags.as_deref());
+    if cli.replay.is_none() {
+        cache.branch = current_branch(&repo_root);
+    }
     let package_filter = cli
         .packages
+        .iter()
+        .map(|pattern| regex::Regex::new(pattern))
+        .collect::<Result<Vec<_>, _>>()?;
+    let package_exclude = cli
+        .exclude_packages
         .as_ref()
         .map(|pattern| regex::Regex::new(pattern))
         .transpose()?;
+    if let Some(pattern) = cli.run_pattern.as_ref() {
+        regex::Regex::new(pattern)?;
+    }
     let cached = cached_packages(&repo_root, &cache);
-    let all_packages = if let Some(packages) = cached {
-        packages
-    } else {
-        let packages = list_packages(&repo_root)?;
-        let _ = update_package_cache(&repo_root, &mut cache, &packages);
-        packages
+    // Without a cached list the TUI starts right away and `go list` runs in
+    // the background. Dry runs and headless runs need the packages up front.
+    // Replays never run go, so they don't list packages at all.
+    let discover_packages = cached.is_none()
+        && !cli.dry_run
+        && !cli.no_tui
+        && cli.control_socket.is_none()
+        && cli.replay.is_none();
+    let patterns = match cli.replay {
+        Some(_) => vec!["./...".to_string()],
+        None => package_patterns(&repo_root)?,
     };
-    let packages = filter_packages(&all_packages, package_filter.as_ref());
-
-    let mut pkg_concurrency = cli.pkg_concurrency.max(1);
-    let mut go_test_p = pkg_concurrency;
-    if cli.sequential {
-        pkg_concurrency = 1;
-        go_test_p = 1;
-    }
+    let all_packages = match cached {
+        _ if cli.replay.is_some() => Vec::new(),
+        Some(packages) => packages,
+        None if discover_packages => Vec::new(),
+        None => {
+            let packages = list_packages(&repo_root, &patterns, cli.tags.as_deref())?;
+            let _ = update_package_cache(&repo_root, &mut cache, &packages);
+            packages
+        }
+    };
+    let packages = filter_packages(&all_packages, &package_filter, package_exclude.as_ref());
+    // While discovering, the only run is the `./...` pattern, a single job;
+    // the counts are resolved again once the packages are known.
+    let (pkg_concurrency, go_test_p) = worker_counts(&cli, packages.len());
 
     let mode = match cli.mode {
+        _ if cli.no_tui => RunMode::All,
         ModeArg::All => RunMode::All,
         ModeArg::Failing => RunMode::Failing,
         ModeArg::Select => RunMode::Selecting,
     };
 
-    let package_cache = cache.package_cache.clone();
+    let mut package_cache = cache.package_cache.clone();
+    let package_cache_generated_at = package_cache.as_ref().map(|cache| cache.generated_at());
     let mut app = App::new(
         repo_root.clone(),
         packages,
         cache,
         mode,
-        package_filter.is_some(),
-        !cli.no_watch,
+        !package_filter.is_empty() || package_exclude.is_some(),
+        !cli.no_watch && !cli.no_tui && cli.control_socket.is_none() && cli.replay.is_none(),
     );
+    app.discovering_packages = discover_packages;
+    app.replaying = cli.replay.is_some();
+    if tags_changed {
+        app.report_error("build tags changed; cleared cached failures");
+    }
+    app.debug = cli.debug;
+    app.package_include = (!cli.packages.is_empty()).then(|| cli.packages.join(", "));
+    app.package_exclude = cli.exclude_packages.clone();
+    app.detail_right = cli.detail_right;
+    app.notify = cli.notify;
+    app.watch_coalesce = Duration::from_millis(cli.watch_coalesce);
+    app.stuck_after = Some(Duration::from_secs(cli.stuck_after)).filter(|after| !after.is_zero());
+    app.registry.max_output_bytes = Some(cli.max_output_bytes).filter(|&bytes| bytes > 0);
+    app.registry.gomaxprocs = gomaxprocs();
+    app.test_all = cli.test_all;
+    app.package_patterns = patterns.clone();
+    if let Some(pattern) = cli.run_pattern.clone() {
+        app.set_run_pattern(pattern);
+    }
+    app.selection.fuzzy = FuzzyOptions {
+        case: match cli.fuzzy_case {
+            FuzzyCaseArg::Smart => FuzzyCase::Smart,
+            FuzzyCaseArg::Respect => FuzzyCase::Respect,
+            FuzzyCaseArg::Ignore => FuzzyCase::Ignore,
+        },
+        name_only: cli.fuzzy_name_only,
+    };
+    app.debug_stats.package_cache_generated_at = package_cache_generated_at;
+    app.runner_config = RunnerConfig {
+        root: repo_root.clone(),
+        pkg_concurrency,
+        go_test_p,
+        no_test_cache: cli.no_test_cache,
+        test_command: cli.test_command.clone().map(|command| command.0),
+        tags: cli.tags.clone(),
+        exec: cli.exec.clone(),
+        env: cli.env.clone(),
+        race: cli.race,
+        cover: cli.cover,
+        failfast: cli.failfast,
+        retries: cli.retries,
+        dump: dump_file(cli.dump.as_deref())?,
+        bench: cli.bench.clone(),
+        go_test_args: cli.go_test_args.clone(),
+        run_timeout: cli
+            .timeout
+            .map(Duration::from_secs)
+            .filter(|timeout| !timeout.is_zero()),
+    };
+
+    if cli.dry_run {
+        if let Some(spec) = app.run_all_spec() {
+            for line in command_lines(&app.runner_config, &spec) {
+                println!("{}", line);
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(path) = cli.control_socket.clone() {
+        run_control(&mut app, &path)?;
+        return Ok(finish(&app, &cli, &cache_path, package_cache)?);
+    }
+
+    if cli.no_tui {
+        let success = run_headless(&mut app);
+        finish(&app, &cli, &cache_path, package_cache)?;
+        std::process::exit(if success { 0 } else { 1 });
+    }
 
     let (app_tx, app_rx) = crossbeam_channel::unbounded();
     let shutdown_tx = app_tx.clone();
@@ -70,42 +205,54 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let _ = shutdown_tx.send(AppEvent::Shutdown);
     })?;
 
+    let replay = cli
+        .replay
+        .as_ref()
+        .map(std::fs::read_to_string)
+        .transpose()?;
     let (runner_event_tx, runner_event_rx) = crossbeam_channel::unbounded();
-    let runner_tx = start_runner(
-        RunnerConfig {
-            root: repo_root.clone(),
-            pkg_concurrency,
-            go_test_p,
-            no_test_cache: cli.no_test_cache,
-            test_command: None,
-        },
-        runner_event_tx,
-    );
+    let runner_tx = start_runner(app.runner_config.clone(), runner_event_tx.clone());
 
-    if app.watch_enabled {
-        let (watch_event_tx, watch_event_rx) = crossbeam_channel::unbounded();
-        if let Err(err) = start_watcher(repo_root.clone(), watch_event_tx) {
-            app.last_error = Some(err.to_string());
-        } else {
-            let app_tx_clone = app_tx.clone();
-            std::thread::spawn(move || {
-                while let Ok(event) = watch_event_rx.recv() {
-                    let _ = app_tx_clone.send(AppEvent::Watch(event));
-                }
-            });
+    let (watch_event_tx, watch_event_rx) = crossbeam_channel::unbounded();
+    let app_tx_clone = app_tx.clone();
+    std::thread::spawn(move || {
+        while let Ok(event) = watch_event_rx.recv() {
+            let _ = app_tx_clone.send(AppEvent::Watch(event));
         }
+    });
+    let watch_debounce = Duration::from_millis(cli.watch_debounce);
+    let mut watcher = None;
+
+    if app.discovering_packages {
+        start_package_discovery(
+            repo_root.clone(),
+            patterns,
+            cli.tags.clone(),
+            package_filter.clone(),
+            package_exclude.clone(),
+            app_tx.clone(),
+        );
     }
 
-    start_input_thread(app_tx.clone());
+    let input_pause = Arc::new(InputPause::default());
+    start_input_thread(app_tx.clone(), input_pause.clone());
     start_tick_thread(app_tx.clone());
 
+    install_panic_hook();
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    app.run_all(&runner_tx);
+    match replay {
+        Some(data) => start_replay(
+            data.lines().map(str::to_string).collect(),
+            Duration::from_millis(cli.replay_delay),
+            runner_event_tx,
+        ),
+        None => app.run_all(&runner_tx),
+    }
 
     terminal.draw(|frame| ui::draw(frame, &app))?;
     let mut last_draw = Instant::now();
@@ -121,7 +268,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     while !should_exit {
         match app_rx.recv_timeout(Duration::from_millis(30)) {
             Ok(event) => {
-                let outcome = handle_app_event(event, &mut app, &runner_tx);
+                let outcome =
+                    handle_app_event(event, &mut app, &cli, &runner_tx, &mut package_cache);
                 should_exit = should_exit || outcome.should_exit;
                 dirty = dirty || outcome.dirty;
                 draw_now = draw_now || outcome.draw_now;
@@ -136,7 +284,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             match app_rx.try_recv() {
                 Ok(event) => {
                     processed += 1;
-                    let outcome = handle_app_event(event, &mut app, &runner_tx);
+                    let outcome =
+                        handle_app_event(event, &mut app, &cli, &runner_tx, &mut package_cache);
                     should_exit = should_exit || outcome.should_exit;
                     dirty = dirty || outcome.dirty;
                     draw_now = draw_now || outcome.draw_now;
@@ -169,6 +318,38 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             break;
         }
 
+        if app.watch_enabled != watcher.is_some() {
+            watcher = None;
+            if app.watch_enabled {
+                match start_watcher(
+                    repo_root.clone(),
+                    &watch_paths,
+                    watch_debounce,
+                    watch_event_tx.clone(),
+                ) {
+                    Ok(handle) => watcher = Some(handle),
+                    Err(err) => {
+                        app.watch_enabled = false;
+                        app.report_error(err.to_string());
+                    }
+                }
+            }
+            draw_now = true;
+        }
+
+        if let Some(path) = app.editor_request.take() {
+            if let Err(err) = open_editor(&mut terminal, &input_pause, &path) {
+                app.report_error(err);
+            }
+            draw_now = true;
+        }
+
+        if app.debug {
+            app.debug_stats.app_events_last_loop = processed;
+            app.debug_stats.runner_events_last_loop = runner_processed;
+            app.debug_stats.pending_runner_events = pending_runner_events.len();
+        }
+
         if !pending_runner_events.is_empty()
             && (draw_now
                 || last_runner_flush.elapsed() >= runner_flush_interval
@@ -182,10 +363,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
             app.handle_runner_events(batch);
+            app.run_due_reruns(&runner_tx);
             last_runner_flush = Instant::now();
             dirty = true;
         }
 
+        if let Some((summary, body)) = app.notification_request.take() {
+            send_notification(summary, body);
+        }
+
         let should_draw = if draw_now {
             true
         } else {
@@ -202,16 +388,182 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let _ = runner_tx.send(RunnerCommand::Shutdown);
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
     terminal.show_cursor()?;
+    finish(&app, &cli, &cache_path, package_cache)?;
+    Ok(())
+}
+
+/// Runs every test once without the TUI, printing failures and a summary.
+/// Returns `false` if any test failed or the run errored.
+fn run_headless(app: &mut App) -> bool {
+    app.errors.clear();
+    let (runner_event_tx, runner_event_rx) = crossbeam_channel::unbounded();
+    let runner_tx = start_runner(app.runner_config.clone(), runner_event_tx);
+    if app.run_all_spec().is_some() {
+        app.run_all(&runner_tx);
+        while let Ok(event) = runner_event_rx.recv() {
+            let finished = matches!(event, RunnerEvent::RunFinished { .. });
+            app.handle_runner_events(vec![event]);
+            if finished && !app.run_state.running {
+                break;
+            }
+        }
+    }
+    let _ = runner_tx.send(RunnerCommand::Shutdown);
+
+    let failed = app.registry.failed_tests();
+    for test in &failed {
+        println!("FAIL {}", test);
+    }
+    let counts = app.status_counts();
+    println!(
+        "{} passed, {} failed, {} skipped, {} not run",
+        counts.passed, counts.failed, counts.skipped, counts.unknown
+    );
+    for error in &app.errors {
+        eprintln!("error: {}", error.message);
+    }
+    failed.is_empty() && app.errors.is_empty()
+}
+
+/// Serves `--control-socket` until ctrl+c: runs commands from editor
+/// clients without the TUI and streams every runner event back to them.
+#[cfg(unix)]
+fn run_control(app: &mut App, path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    let (command_tx, command_rx) = crossbeam_channel::unbounded();
+    let clients = start_control_server(path, command_tx)?;
+    let (shutdown_tx, shutdown_rx) = crossbeam_channel::unbounded();
+    ctrlc::set_handler(move || {
+        let _ = shutdown_tx.send(());
+    })?;
+    let (runner_event_tx, runner_event_rx) = crossbeam_channel::unbounded();
+    let runner_tx = start_runner(app.runner_config.clone(), runner_event_tx);
+    serve_control(
+        app,
+        &clients,
+        &command_rx,
+        &runner_tx,
+        &runner_event_rx,
+        &shutdown_rx,
+    );
+    let _ = runner_tx.send(RunnerCommand::Shutdown);
+    let _ = std::fs::remove_file(path);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn run_control(_app: &mut App, _path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    Err("--control-socket needs unix domain sockets, which this platform lacks".into())
+}
+
+/// `(pkg_concurrency, go_test_p)` for `package_count` packages, after
+/// `--sequential`, `--pkg-workers` and `--go-p`.
+fn worker_counts(cli: &Cli, package_count: usize) -> (usize, usize) {
+    let (mut pkg_concurrency, mut go_test_p) =
+        cli.pkg_concurrency.resolve(num_cpus::get(), package_count);
+    if cli.sequential {
+        pkg_concurrency = 1;
+        go_test_p = 1;
+    }
+    if let Some(workers) = cli.pkg_workers {
+        pkg_concurrency = workers.max(1);
+    }
+    if let Some(p) = cli.go_p {
+        go_test_p = p.max(1);
+    }
+    (pkg_concurrency, go_test_p)
+}
+
+/// Saves the cache and writes the requested exit reports.
+fn finish(
+    app: &App,
+    cli: &Cli,
+    cache_path: &std::path::Path,
+    package_cache: Option<PackageCache>,
+) -> std::io::Result<()> {
     let mut final_cache = app.cache_state();
     final_cache.package_cache = package_cache;
-    let _ = save_cache(&cache_path, &final_cache);
+    final_cache.tags = cli.tags.clone();
+    // Replayed results are someone else's run; keep the cache as it was.
+    if cli.replay.is_none() {
+        let _ = save_cache(cache_path, &final_cache);
+    }
+    if let Some(path) = cli.tap.as_ref() {
+        write_report(path, tap_report(&app.registry))?;
+    }
+    if let Some(path) = cli.junit.as_ref() {
+        write_report(path, junit_report(&app.registry))?;
+    }
+    if let Some(path) = cli.export_json.as_ref() {
+        write_report(path, json_report(&app.registry))?;
+    }
     Ok(())
 }
 
-fn start_input_thread(tx: crossbeam_channel::Sender<AppEvent>) {
+/// Opens the `--dump` file for the runner to copy raw test output into.
+fn dump_file(path: Option<&std::path::Path>) -> std::io::Result<Option<Arc<Mutex<File>>>> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let file = File::options().create(true).append(true).open(path)?;
+    Ok(Some(Arc::new(Mutex::new(file))))
+}
+
+/// Writes an exit report to `path`, or to stdout when `path` is `-`.
+fn write_report(path: &std::path::Path, report: String) -> std::io::Result<()> {
+    if path.as_os_str() == "-" {
+        print!("{}", report);
+        Ok(())
+    } else {
+        std::fs::write(path, report)
+    }
+}
+
+/// Lists packages on a background thread so the TUI can start before a slow
+/// `go list` in a large repo finishes.
+fn start_package_discovery(
+    root: std::path::PathBuf,
+    patterns: Vec<String>,
+    tags: Option<String>,
+    include: Vec<regex::Regex>,
+    exclude: Option<regex::Regex>,
+    tx: crossbeam_channel::Sender<AppEvent>,
+) {
+    std::thread::spawn(move || {
+        let listing = list_packages(&root, &patterns, tags.as_deref())
+            .map(|packages| {
+                let mut state = CacheState::default();
+                let _ = update_package_cache(&root, &mut state, &packages);
+                PackageListing {
+                    packages: filter_packages(&packages, &include, exclude.as_ref()),
+                    cache: state.package_cache,
+                }
+            })
+            .map_err(|err| err.to_string());
+        let _ = tx.send(AppEvent::Packages(listing));
+    });
+}
+
+/// Lets the main loop stop the input thread from reading the terminal while
+/// another program (the editor) owns it.
+#[derive(Default)]
+struct InputPause {
+    paused: AtomicBool,
+    reading: Mutex<()>,
+}
+
+fn start_input_thread(tx: crossbeam_channel::Sender<AppEvent>, pause: Arc<InputPause>) {
     std::thread::spawn(move || loop {
+        if pause.paused.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(50));
+            continue;
+        }
+        let _reading = pause.reading.lock().unwrap_or_else(|err| err.into_inner());
         if event::poll(Duration::from_millis(50)).unwrap_or(false) {
             if let Ok(event) = event::read() {
                 let _ = tx.send(AppEvent::Input(event));
@@ -220,6 +572,76 @@ fn start_input_thread(tx: crossbeam_channel::Sender<AppEvent>) {
     });
 }
 
+/// Restores the terminal before the panic message is printed, so a crash
+/// doesn't leave the shell in raw mode on the alternate screen.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(
+            std::io::stdout(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            crossterm::cursor::Show
+        );
+        default_hook(info);
+    }));
+}
+
+/// Shows a desktop notification in the background. Failures are ignored:
+/// not every system has a notification daemon.
+fn send_notification(summary: String, body: String) {
+    std::thread::spawn(move || {
+        let _ = notify_rust::Notification::new()
+            .appname("gest")
+            .summary(&summary)
+            .body(&body)
+            .show();
+    });
+}
+
+/// Suspends the TUI, runs `$EDITOR` (default `vi`) on `path` and restores the
+/// TUI once the editor exits.
+fn open_editor(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    pause: &InputPause,
+    path: &std::path::Path,
+) -> Result<(), String> {
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+    let mut words = shell_words::split(&editor)
+        .map_err(|err| format!("invalid $EDITOR {:?}: {}", editor, err))?;
+    if words.is_empty() {
+        words.push("vi".to_string());
+    }
+
+    pause.paused.store(true, Ordering::SeqCst);
+    let _reading = pause.reading.lock().unwrap_or_else(|err| err.into_inner());
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    );
+    let status = std::process::Command::new(&words[0])
+        .args(&words[1..])
+        .arg(path)
+        .status();
+    let _ = enable_raw_mode();
+    let _ = execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    );
+    let _ = terminal.clear();
+    pause.paused.store(false, Ordering::SeqCst);
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("{} exited with {}", words[0], status)),
+        Err(err) => Err(format!("could not start {}: {}", words[0], err)),
+    }
+}
+
 fn start_tick_thread(tx: crossbeam_channel::Sender<AppEvent>) {
     std::thread::spawn(move || loop {
         std::thread::sleep(Duration::from_millis(200));
@@ -236,9 +658,37 @@ struct AppEventOutcome {
 fn handle_app_event(
     event: AppEvent,
     app: &mut App,
+    cli: &Cli,
     runner_tx: &crossbeam_channel::Sender<RunnerCommand>,
+    package_cache: &mut Option<PackageCache>,
 ) -> AppEventOutcome {
     match event {
+        AppEvent::Packages(listing) => {
+            match listing {
+                Ok(listing) => {
+                    *package_cache = listing.cache;
+                    app.debug_stats.package_cache_generated_at =
+                        package_cache.as_ref().map(|cache| cache.generated_at());
+                    let (pkg_concurrency, go_test_p) = worker_counts(cli, listing.packages.len());
+                    app.runner_config.pkg_concurrency = pkg_concurrency;
+                    app.runner_config.go_test_p = go_test_p;
+                    let _ = runner_tx.send(RunnerCommand::SetWorkers {
+                        pkg_concurrency,
+                        go_test_p,
+                    });
+                    app.set_packages(listing.packages, runner_tx);
+                }
+                Err(err) => {
+                    app.discovering_packages = false;
+                    app.report_error(format!("listing packages failed: {}", err));
+                }
+            }
+            AppEventOutcome {
+                should_exit: false,
+                draw_now: true,
+                dirty: true,
+            }
+        }
         AppEvent::Input(event) => AppEventOutcome {
             should_exit: app.handle_input(event, runner_tx),
             draw_now: true,
@@ -252,11 +702,16 @@ fn handle_app_event(
                 dirty: true,
             }
         }
-        AppEvent::Tick => AppEventOutcome {
-            should_exit: false,
-            draw_now: false,
-            dirty: false,
-        },
+        // Redraw while running so live durations and the spinner keep ticking.
+        AppEvent::Tick => {
+            app.tick();
+            app.run_due_reruns(runner_tx);
+            AppEventOutcome {
+                should_exit: false,
+                draw_now: false,
+                dirty: app.run_state.running,
+            }
+        }
         AppEvent::Shutdown => AppEventOutcome {
             should_exit: true,
             draw_now: false,
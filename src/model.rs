@@ -1,10 +1,10 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
 
-use crate::go::{GoTestAction, GoTestEvent};
+use crate::go::{benchmark_name, GoTestAction, GoTestEvent};
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TestId {
@@ -18,21 +18,81 @@ impl fmt::Display for TestId {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TestStatus {
     Unknown,
     Running,
     Passed,
     Failed,
+    Skipped,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct TestCase {
     pub status: TestStatus,
     pub output: String,
+    /// Output exactly as go emitted it, before `sanitize_output`.
+    #[serde(skip)]
+    pub raw_output: String,
+    /// Output of the previous finished run, for the output diff.
+    #[serde(skip)]
+    pub prev_output: String,
+    /// `GoTestEvent::seq` of the first output line of the current run.
+    #[serde(skip)]
+    pub output_seq: Option<u64>,
     pub panic: bool,
+    /// The race detector reported a data race in this test (`-race`).
+    #[serde(skip)]
+    pub race: bool,
+    #[serde(skip)]
     pub has_children: bool,
+    #[serde(skip)]
     pub last_update: Option<Instant>,
+    /// When the current run of this test started.
+    #[serde(skip)]
+    pub started_at: Option<Instant>,
+    /// Go's reported `Elapsed` for the last finished run.
+    #[serde(rename = "duration_secs", serialize_with = "serialize_secs")]
+    pub duration: Option<Duration>,
+    /// The last result was replayed from go's test cache (`(cached)`).
+    #[serde(skip)]
+    pub cached: bool,
+    /// The last event was `pause`: a `t.Parallel()` test waiting for its
+    /// turn, not a hung one.
+    #[serde(skip)]
+    pub paused: bool,
+}
+
+fn serialize_secs<S: serde::Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let secs = duration.map(|duration| duration.as_secs_f64());
+    secs.serialize(serializer)
+}
+
+impl TestCase {
+    /// Clears the output for a new run, keeping a finished run's output as
+    /// `prev_output`. A test already marked running has nothing to keep.
+    pub fn reset_output(&mut self) {
+        if self.status == TestStatus::Running {
+            self.output.clear();
+        } else {
+            self.prev_output = std::mem::take(&mut self.output);
+        }
+        self.raw_output.clear();
+    }
+
+    /// Time spent in this test: live while running, otherwise the reported
+    /// duration of the last run.
+    pub fn elapsed(&self) -> Option<Duration> {
+        if self.status == TestStatus::Running {
+            self.started_at.map(|start| start.elapsed())
+        } else {
+            self.duration
+        }
+    }
 }
 
 impl Default for TestCase {
@@ -40,9 +100,17 @@ impl Default for TestCase {
         Self {
             status: TestStatus::Unknown,
             output: String::new(),
+            raw_output: String::new(),
+            prev_output: String::new(),
+            output_seq: None,
             panic: false,
+            race: false,
             has_children: false,
             last_update: None,
+            started_at: None,
+            duration: None,
+            cached: false,
+            paused: false,
         }
     }
 }
@@ -54,11 +122,52 @@ pub struct TestRegistry {
     order_index: HashMap<TestId, usize>,
     parents: HashSet<TestId>,
     package_state: HashMap<String, PackageState>,
+    outcomes: HashMap<TestId, OutcomeTally>,
+    coverage: HashMap<String, f64>,
+    package_durations: HashMap<String, Duration>,
+    /// Stop capturing a test's output once it grows past this many bytes.
+    pub max_output_bytes: Option<usize>,
+    /// GOMAXPROCS of the tests, whose `-<n>` suffix benchmark names drop.
+    pub gomaxprocs: usize,
+}
+
+/// How often a test passed and failed during this session.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct OutcomeTally {
+    pub passed: u32,
+    pub failed: u32,
 }
 
+/// Name of the synthetic entry that holds package-level failure output when
+/// the package fails but none of its tests did (e.g. `TestMain` cleanup).
+pub const PACKAGE_TEARDOWN_TEST: &str = "<package teardown>";
+
+/// Name of the synthetic entry holding compiler output for a package that
+/// failed to build, so no test in it ever ran.
+pub const PACKAGE_BUILD_TEST: &str = "(build)";
+
+/// Whether `name` is one of the synthetic package-level entries rather than
+/// a real test.
+pub fn is_package_entry(name: &str) -> bool {
+    name == PACKAGE_BUILD_TEST || name == PACKAGE_TEARDOWN_TEST
+}
+
+/// Appended once to output that hit `TestRegistry::max_output_bytes`.
+pub const OUTPUT_TRUNCATED: &str = "… [output truncated]\n";
+
 #[derive(Default, Debug)]
 struct PackageState {
     current_test: Option<String>,
+    /// Output printed outside of any test since the package started.
+    output: String,
+    saw_test_failure: bool,
+    ran_test: bool,
+    /// Tests started in this package run, marked cached if go reports
+    /// `(cached)` for the package.
+    run_tests: Vec<String>,
+    cached: bool,
+    build_failed: bool,
+    build_output: String,
 }
 
 impl TestRegistry {
@@ -82,15 +191,26 @@ impl TestRegistry {
                         name: test.clone(),
                     };
                     let case = self.tests.entry(id.clone()).or_default();
+                    case.reset_output();
                     case.status = TestStatus::Running;
-                    case.output.clear();
+                    case.output_seq = None;
                     case.panic = false;
+                    case.race = false;
                     case.last_update = Some(Instant::now());
+                    case.started_at = case.last_update;
+                    case.duration = None;
+                    case.cached = false;
+                    case.paused = false;
                     self.track_order(id);
-                    self.package_state
-                        .entry(package.clone())
-                        .or_default()
-                        .current_test = Some(test.clone());
+                    let state = self.package_state.entry(package.clone()).or_default();
+                    state.current_test = Some(test.clone());
+                    state.ran_test = true;
+                    state.run_tests.push(test.clone());
+                }
+            }
+            GoTestAction::BuildOutput => {
+                if let Some(output) = event.output.as_ref() {
+                    self.append_build_output(&package, &sanitize_output(output));
                 }
             }
             GoTestAction::Pass | GoTestAction::Fail | GoTestAction::Skip => {
@@ -103,42 +223,102 @@ impl TestRegistry {
                     case.status = match event.action {
                         GoTestAction::Pass => TestStatus::Passed,
                         GoTestAction::Fail => TestStatus::Failed,
-                        GoTestAction::Skip => TestStatus::Unknown,
+                        GoTestAction::Skip => TestStatus::Skipped,
                         _ => case.status,
                     };
+                    case.duration = match event.action {
+                        GoTestAction::Skip => None,
+                        _ => event
+                            .elapsed
+                            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                            .or_else(|| case.started_at.map(|start| start.elapsed())),
+                    };
                     case.last_update = Some(Instant::now());
+                    case.paused = false;
+                    let tally = self.outcomes.entry(id.clone()).or_default();
+                    match event.action {
+                        GoTestAction::Pass => tally.passed += 1,
+                        GoTestAction::Fail => tally.failed += 1,
+                        _ => {}
+                    }
                     self.track_order(id);
-                    self.package_state
-                        .entry(package.clone())
-                        .or_default()
-                        .current_test = Some(test.clone());
+                    let state = self.package_state.entry(package.clone()).or_default();
+                    state.current_test = Some(test.clone());
+                    if event.action == GoTestAction::Fail {
+                        state.saw_test_failure = true;
+                    }
+                } else {
+                    let elapsed = event
+                        .elapsed
+                        .and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+                    if let Some(elapsed) = elapsed {
+                        self.package_durations.insert(package.clone(), elapsed);
+                    }
+                    self.finish_package(&package, event.action == GoTestAction::Fail);
                 }
             }
             GoTestAction::Output => {
                 if event.test.is_none() {
                     if let Some(output) = event.output.as_ref() {
-                        if is_harness_output(output) {
+                        if let Some(percent) = parse_coverage(output) {
+                            self.coverage.insert(package.clone(), percent);
+                        }
+                        if is_cached_result(output) {
+                            self.package_state
+                                .entry(package.clone())
+                                .or_default()
+                                .cached = true;
+                        }
+                        if output.contains("[build failed]") || output.contains("[setup failed]") {
+                            self.package_state
+                                .entry(package.clone())
+                                .or_default()
+                                .build_failed = true;
+                        }
+                        if is_harness_output(output) || output.trim().starts_with("coverage:") {
                             return;
                         }
                     }
                 }
+                if event.test.is_none() {
+                    if let Some(output) = event.output.as_ref() {
+                        let state = self.package_state.entry(package.clone()).or_default();
+                        let cap = self.max_output_bytes;
+                        append_capped(&mut state.output, &sanitize_output(output), cap);
+                    }
+                }
                 let current_test = self
                     .package_state
                     .get(&package)
                     .and_then(|state| state.current_test.clone());
-                let target = event.test.clone().or(current_test);
+                let benchmark = event
+                    .output
+                    .as_deref()
+                    .and_then(|output| benchmark_name(output, self.gomaxprocs))
+                    .filter(|_| event.test.is_none())
+                    .map(str::to_string);
+                let target = event.test.clone().or(benchmark.clone()).or(current_test);
                 if let Some(test) = target {
                     let id = TestId {
                         package: package.clone(),
                         name: test,
                     };
                     let case = self.tests.entry(id.clone()).or_default();
+                    if benchmark.is_some() && case.status != TestStatus::Failed {
+                        case.status = TestStatus::Passed;
+                    }
                     if let Some(output) = event.output.as_ref() {
+                        case.output_seq.get_or_insert(event.seq);
                         let sanitized = sanitize_output(output);
-                        case.output.push_str(&sanitized);
+                        let cap = self.max_output_bytes;
+                        append_capped(&mut case.output, &sanitized, cap);
+                        append_capped(&mut case.raw_output, output, cap);
                         if is_panic_output(output) {
                             case.panic = true;
                         }
+                        if is_race_output(output) {
+                            case.race = true;
+                        }
                     }
                     case.last_update = Some(Instant::now());
                     self.track_order(id);
@@ -150,7 +330,26 @@ impl TestRegistry {
                         .current_test = Some(test.clone());
                 }
             }
-            GoTestAction::Other => {}
+            // Untagged output while a parallel test is paused belongs to
+            // whichever test runs next, not the paused one.
+            GoTestAction::Pause => {
+                self.set_paused(event, true);
+                if let Some(state) = self.package_state.get_mut(&package) {
+                    if state.current_test.is_some() && state.current_test == event.test {
+                        state.current_test = None;
+                    }
+                }
+            }
+            GoTestAction::Cont => {
+                self.set_paused(event, false);
+                if let Some(test) = event.test.as_ref() {
+                    self.package_state
+                        .entry(package.clone())
+                        .or_default()
+                        .current_test = Some(test.clone());
+                }
+            }
+            GoTestAction::Start | GoTestAction::Other => {}
         }
     }
 
@@ -162,10 +361,64 @@ impl TestRegistry {
         self.tests.get_mut(id)
     }
 
+    pub fn len(&self) -> usize {
+        self.tests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tests.is_empty()
+    }
+
+    fn set_paused(&mut self, event: &GoTestEvent, paused: bool) {
+        let Some(name) = event.test.clone() else {
+            return;
+        };
+        let id = TestId {
+            package: event.package.clone(),
+            name,
+        };
+        if let Some(case) = self.tests.get_mut(&id) {
+            case.paused = paused;
+        }
+    }
+
+    /// Leaf tests that are still running.
+    pub fn running_tests(&self) -> impl Iterator<Item = (&TestId, &TestCase)> {
+        self.tests
+            .iter()
+            .filter(|(id, case)| case.status == TestStatus::Running && !self.parents.contains(*id))
+    }
+
+    /// Every package with a known test.
+    pub fn packages(&self) -> HashSet<&str> {
+        self.tests.keys().map(|id| id.package.as_str()).collect()
+    }
+
     pub fn is_parent(&self, id: &TestId) -> bool {
         self.parents.contains(id)
     }
 
+    /// Walks from `id` up through its parent tests, closest parent first, and
+    /// returns the ones that recorded a panic. Go frequently prints a subtest
+    /// panic without a `Test` field, so it lands on a hidden parent.
+    pub fn panicked_parents(&self, id: &TestId) -> Vec<(TestId, &TestCase)> {
+        let mut parents = Vec::new();
+        let mut name = id.name.as_str();
+        while let Some((parent, _)) = name.rsplit_once('/') {
+            name = parent;
+            let parent_id = TestId {
+                package: id.package.clone(),
+                name: parent.to_string(),
+            };
+            if let Some(case) = self.tests.get(&parent_id) {
+                if case.panic {
+                    parents.push((parent_id, case));
+                }
+            }
+        }
+        parents
+    }
+
     pub fn leaf_tests(&self) -> Vec<TestId> {
         self.order
             .iter()
@@ -174,6 +427,40 @@ impl TestRegistry {
             .collect()
     }
 
+    /// Statement coverage reported by the last `-cover` run of `package`.
+    pub fn coverage(&self, package: &str) -> Option<f64> {
+        self.coverage.get(package).copied()
+    }
+
+    /// Total time of the last run of `package`, from its final pass/fail event.
+    pub fn package_duration(&self, package: &str) -> Option<Duration> {
+        self.package_durations.get(package).copied()
+    }
+
+    pub fn outcomes(&self, id: &TestId) -> OutcomeTally {
+        self.outcomes.get(id).copied().unwrap_or_default()
+    }
+
+    /// Counts a failed attempt that never reached the registry as a `Fail`
+    /// event because the test was retried.
+    pub fn record_failed_attempt(&mut self, id: &TestId) {
+        self.outcomes.entry(id.clone()).or_default().failed += 1;
+    }
+
+    pub fn is_flaky(&self, id: &TestId) -> bool {
+        let tally = self.outcomes(id);
+        tally.passed > 0 && tally.failed > 0
+    }
+
+    /// Tests that have both passed and failed during this session.
+    pub fn flaky_tests(&self) -> Vec<TestId> {
+        self.order
+            .iter()
+            .filter(|id| self.is_flaky(id))
+            .cloned()
+            .collect()
+    }
+
     pub fn failed_tests(&self) -> Vec<TestId> {
         self.order
             .iter()
@@ -207,21 +494,129 @@ impl TestRegistry {
                 continue;
             }
             if let Some(start) = since {
-                if case.last_update.map(|ts| ts >= start).unwrap_or(false) {
-                    case.status = status;
-                    case.last_update = Some(now);
+                if !case.last_update.map(|ts| ts >= start).unwrap_or(false) {
+                    continue;
                 }
-            } else {
-                case.status = status;
-                case.last_update = Some(now);
+            }
+            case.status = status;
+            case.last_update = Some(now);
+            case.duration = case.started_at.map(|start| now - start);
+        }
+    }
+
+    /// Marks tests still running as `Unknown`: their process was killed, so
+    /// no result is coming. Parents included.
+    pub fn abandon_running(&mut self) {
+        for case in self.tests.values_mut() {
+            if case.status == TestStatus::Running {
+                case.status = TestStatus::Unknown;
+                case.paused = false;
+            }
+        }
+    }
+
+    /// Drops captured output of every test that is not running and forgets
+    /// passed tests entirely. Running tests are left alone.
+    pub fn clear_finished(&mut self) {
+        let passed = |case: &TestCase| case.status == TestStatus::Passed;
+        self.tests.retain(|_, case| !passed(case));
+        for case in self.tests.values_mut() {
+            if case.status != TestStatus::Running {
+                case.output.clear();
+                case.raw_output.clear();
+                case.prev_output.clear();
+                case.output_seq = None;
+                case.panic = false;
+                case.race = false;
             }
         }
+        let tests = &self.tests;
+        self.parents.retain(|id| tests.contains_key(id));
+        self.order.retain(|id| tests.contains_key(id));
+        self.reindex_order();
+    }
+
+    fn reindex_order(&mut self) {
+        self.order_index = self
+            .order
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (id.clone(), index))
+            .collect();
     }
 
     pub fn order_index(&self, id: &TestId) -> usize {
         self.order_index.get(id).cloned().unwrap_or(usize::MAX)
     }
 
+    /// Handles the package-level pass/fail event. A failing package without
+    /// any failing test gets a synthetic entry so the failure shows up in the
+    /// list: `(build)` with the compiler output when it never got to run a
+    /// test, otherwise a teardown entry with the output printed outside of
+    /// tests.
+    fn finish_package(&mut self, package: &str, failed: bool) {
+        let state = self.package_state.remove(package).unwrap_or_default();
+        if state.cached {
+            for name in &state.run_tests {
+                let id = TestId {
+                    package: package.to_string(),
+                    name: name.clone(),
+                };
+                if let Some(case) = self.tests.get_mut(&id) {
+                    case.cached = true;
+                }
+            }
+        }
+        let build_failed =
+            !state.ran_test && (state.build_failed || !state.build_output.is_empty());
+        for name in [PACKAGE_BUILD_TEST, PACKAGE_TEARDOWN_TEST] {
+            let id = TestId {
+                package: package.to_string(),
+                name: name.to_string(),
+            };
+            let is_failure =
+                failed && !state.saw_test_failure && build_failed == (name == PACKAGE_BUILD_TEST);
+            if is_failure {
+                let case = self.tests.entry(id.clone()).or_default();
+                case.status = TestStatus::Failed;
+                case.output = if build_failed {
+                    format!("{}{}", state.build_output, state.output)
+                } else {
+                    state.output.clone()
+                };
+                case.last_update = Some(Instant::now());
+                self.track_order(id);
+            } else if self.tests.remove(&id).is_some() {
+                // Not a test: once the package is fine again it goes away.
+                self.outcomes.remove(&id);
+                self.order.retain(|test| *test != id);
+                self.reindex_order();
+            }
+        }
+    }
+
+    /// Compiler output can arrive (on stderr) after the package already
+    /// finished; it then goes straight onto the failed `(build)` entry.
+    fn append_build_output(&mut self, package: &str, output: &str) {
+        if !self.package_state.contains_key(package) {
+            let id = TestId {
+                package: package.to_string(),
+                name: PACKAGE_BUILD_TEST.to_string(),
+            };
+            if let Some(case) = self.tests.get_mut(&id) {
+                if case.status == TestStatus::Failed {
+                    case.output.push_str(output);
+                    return;
+                }
+            }
+        }
+        self.package_state
+            .entry(package.to_string())
+            .or_default()
+            .build_output
+            .push_str(output);
+    }
+
     fn insert_test(&mut self, id: TestId, case: TestCase) {
         self.track_order(id.clone());
         self.tests.insert(id, case);
@@ -261,6 +656,26 @@ fn is_panic_output(line: &str) -> bool {
         || trimmed.contains("panic:")
 }
 
+/// The race detector opens each report with `WARNING: DATA RACE`.
+fn is_race_output(line: &str) -> bool {
+    line.contains("WARNING: DATA RACE")
+}
+
+/// Extracts the percentage from go's `coverage: 42.1% of statements`, which
+/// appears on its own line and at the end of the `ok` summary line.
+fn parse_coverage(line: &str) -> Option<f64> {
+    let (_, rest) = line.split_once("coverage: ")?;
+    let (percent, _) = rest.split_once('%')?;
+    percent.trim().parse().ok()
+}
+
+/// Go's tab-separated package summary for a result replayed from the test
+/// cache, e.g. `ok  example  (cached)`.
+fn is_cached_result(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    (trimmed.starts_with("ok ") || trimmed.starts_with("ok\t")) && line.contains("\t(cached)")
+}
+
 fn is_harness_output(line: &str) -> bool {
     let trimmed = line.trim();
     trimmed == "PASS"
@@ -293,6 +708,17 @@ fn sanitize_output(output: &str) -> String {
     cleaned
 }
 
+fn append_capped(buffer: &mut String, text: &str, cap: Option<usize>) {
+    match cap {
+        Some(cap) if buffer.len() >= cap => {
+            if !buffer.ends_with(OUTPUT_TRUNCATED) {
+                buffer.push_str(OUTPUT_TRUNCATED);
+            }
+        }
+        _ => buffer.push_str(text),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -308,6 +734,7 @@ mod tests {
                 test: Some("TestFoo".to_string()),
                 output: None,
                 elapsed: None,
+                ..Default::default()
             },
             GoTestEvent {
                 action: GoTestAction::Run,
@@ -315,6 +742,7 @@ mod tests {
                 test: Some("TestFoo/Sub".to_string()),
                 output: None,
                 elapsed: None,
+                ..Default::default()
             },
             GoTestEvent {
                 action: GoTestAction::Pass,
@@ -322,6 +750,7 @@ mod tests {
                 test: Some("TestFoo/Sub".to_string()),
                 output: None,
                 elapsed: None,
+                ..Default::default()
             },
         ];
 
@@ -334,6 +763,185 @@ mod tests {
         assert_eq!(leaf[0].name, "TestFoo/Sub");
     }
 
+    #[test]
+    fn records_durations_per_test_and_resets_on_rerun() {
+        let mut registry = TestRegistry::default();
+        let event = |action, name: &str, elapsed| GoTestEvent {
+            action,
+            package: "example".to_string(),
+            test: Some(name.to_string()),
+            output: None,
+            elapsed,
+            ..Default::default()
+        };
+        registry.apply_event(&event(GoTestAction::Run, "TestFoo", None));
+        registry.apply_event(&event(GoTestAction::Run, "TestFoo/Sub", None));
+        registry.apply_event(&event(GoTestAction::Pass, "TestFoo/Sub", Some(0.25)));
+        registry.apply_event(&event(GoTestAction::Fail, "TestFoo", Some(0.5)));
+
+        let id = |name: &str| TestId {
+            package: "example".to_string(),
+            name: name.to_string(),
+        };
+        let duration = |registry: &TestRegistry, name| registry.case(&id(name)).unwrap().duration;
+        let ms = |millis| Some(Duration::from_millis(millis));
+        assert_eq!(duration(&registry, "TestFoo/Sub"), ms(250));
+        assert_eq!(duration(&registry, "TestFoo"), ms(500));
+
+        registry.apply_event(&event(GoTestAction::Run, "TestFoo", None));
+        assert_eq!(duration(&registry, "TestFoo"), None);
+        assert!(registry.case(&id("TestFoo")).unwrap().elapsed().is_some());
+    }
+
+    #[test]
+    fn flags_tests_that_both_pass_and_fail() {
+        let mut registry = TestRegistry::default();
+        let event = |action, name: &str| GoTestEvent {
+            action,
+            package: "example".to_string(),
+            test: Some(name.to_string()),
+            output: None,
+            elapsed: None,
+            ..Default::default()
+        };
+        for action in [GoTestAction::Pass, GoTestAction::Fail, GoTestAction::Pass] {
+            registry.apply_event(&event(GoTestAction::Run, "TestFlaky"));
+            registry.apply_event(&event(action, "TestFlaky"));
+            registry.apply_event(&event(GoTestAction::Run, "TestStable"));
+            registry.apply_event(&event(GoTestAction::Pass, "TestStable"));
+        }
+
+        let flaky = registry.flaky_tests();
+        assert_eq!(flaky.len(), 1);
+        assert_eq!(flaky[0].name, "TestFlaky");
+        let tally = registry.outcomes(&flaky[0]);
+        assert_eq!((tally.passed, tally.failed), (2, 1));
+    }
+
+    #[test]
+    fn records_package_elapsed_time() {
+        let mut registry = TestRegistry::default();
+        let event = |test: Option<&str>, action, elapsed| GoTestEvent {
+            action,
+            package: "example".to_string(),
+            test: test.map(str::to_string),
+            output: None,
+            elapsed,
+            ..Default::default()
+        };
+        registry.apply_event(&event(Some("TestFoo"), GoTestAction::Run, None));
+        registry.apply_event(&event(Some("TestFoo"), GoTestAction::Pass, Some(0.2)));
+        assert_eq!(registry.package_duration("example"), None);
+
+        registry.apply_event(&event(None, GoTestAction::Pass, Some(1.5)));
+        assert_eq!(
+            registry.package_duration("example"),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(registry.package_duration("other"), None);
+    }
+
+    #[test]
+    fn records_package_coverage_without_attributing_it_to_tests() {
+        let mut registry = TestRegistry::default();
+        let event = |test: Option<&str>, action, output: Option<&str>| GoTestEvent {
+            action,
+            package: "example".to_string(),
+            test: test.map(str::to_string),
+            output: output.map(str::to_string),
+            elapsed: None,
+            ..Default::default()
+        };
+        registry.apply_event(&event(Some("TestFoo"), GoTestAction::Run, None));
+        registry.apply_event(&event(Some("TestFoo"), GoTestAction::Pass, None));
+        registry.apply_event(&event(
+            None,
+            GoTestAction::Output,
+            Some("coverage: 42.1% of statements\n"),
+        ));
+        assert_eq!(registry.coverage("example"), Some(42.1));
+
+        registry.apply_event(&event(
+            None,
+            GoTestAction::Output,
+            Some("ok  \texample\t0.01s\tcoverage: 50.0% of statements\n"),
+        ));
+        assert_eq!(registry.coverage("example"), Some(50.0));
+        let id = TestId {
+            package: "example".to_string(),
+            name: "TestFoo".to_string(),
+        };
+        assert_eq!(registry.case(&id).unwrap().output, "");
+        assert_eq!(parse_coverage("coverage: [no statements]\n"), None);
+    }
+
+    #[test]
+    fn captures_benchmark_results_per_benchmark() {
+        let mut registry = TestRegistry {
+            gomaxprocs: 8,
+            ..Default::default()
+        };
+        for output in [
+            "goos: linux\n",
+            "BenchmarkFoo-8   \t 1000000\t      1053 ns/op\n",
+            "BenchmarkBar-8   \t 2000\t      99 ns/op\n",
+        ] {
+            registry.apply_event(&GoTestEvent {
+                action: GoTestAction::Output,
+                package: "example".to_string(),
+                test: None,
+                output: Some(output.to_string()),
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        let id = TestId {
+            package: "example".to_string(),
+            name: "BenchmarkFoo".to_string(),
+        };
+        let case = registry.case(&id).unwrap();
+        assert_eq!(case.status, TestStatus::Passed);
+        assert!(case.output.contains("1053 ns/op"));
+        assert!(!case.output.contains("99 ns/op"));
+        assert_eq!(registry.leaf_tests().len(), 2);
+    }
+
+    #[test]
+    fn records_build_failure_as_build_entry() {
+        let mut registry = TestRegistry::default();
+        let event = |action, output: Option<&str>| GoTestEvent {
+            action,
+            package: "example".to_string(),
+            test: None,
+            output: output.map(str::to_string),
+            elapsed: None,
+            ..Default::default()
+        };
+        registry.apply_event(&event(GoTestAction::BuildOutput, Some("# example\n")));
+        registry.apply_event(&event(
+            GoTestAction::BuildOutput,
+            Some("./foo.go:3:1: syntax error\n"),
+        ));
+        registry.apply_event(&event(
+            GoTestAction::Output,
+            Some("FAIL\texample [build failed]\n"),
+        ));
+        registry.apply_event(&event(GoTestAction::Fail, None));
+
+        let id = TestId {
+            package: "example".to_string(),
+            name: PACKAGE_BUILD_TEST.to_string(),
+        };
+        let case = registry.case(&id).unwrap();
+        assert_eq!(case.status, TestStatus::Failed);
+        assert_eq!(case.output, "# example\n./foo.go:3:1: syntax error\n");
+        assert_eq!(registry.failed_tests(), vec![id.clone()]);
+
+        registry.apply_event(&event(GoTestAction::Pass, None));
+        assert!(registry.case(&id).is_none());
+        assert!(registry.leaf_tests().is_empty());
+    }
+
     #[test]
     fn detects_panic_output() {
         let mut registry = TestRegistry::default();
@@ -343,6 +951,7 @@ mod tests {
             test: Some("TestFoo".to_string()),
             output: Some("panic: boom".to_string()),
             elapsed: None,
+            ..Default::default()
         };
 
         registry.apply_event(&event);
@@ -354,6 +963,74 @@ mod tests {
         assert!(case.panic);
     }
 
+    #[test]
+    fn detects_data_race_reports() {
+        let mut registry = TestRegistry::default();
+        let event = |action, output: Option<&str>| GoTestEvent {
+            action,
+            package: "example".to_string(),
+            test: Some("TestFoo".to_string()),
+            output: output.map(str::to_string),
+            elapsed: None,
+            ..Default::default()
+        };
+        let id = TestId {
+            package: "example".to_string(),
+            name: "TestFoo".to_string(),
+        };
+        registry.apply_event(&event(GoTestAction::Run, None));
+        registry.apply_event(&event(GoTestAction::Output, Some("==================\n")));
+        registry.apply_event(&event(GoTestAction::Output, Some("WARNING: DATA RACE\n")));
+        assert!(registry.case(&id).unwrap().race);
+        assert!(!registry.case(&id).unwrap().panic);
+
+        registry.apply_event(&event(GoTestAction::Run, None));
+        assert!(!registry.case(&id).unwrap().race);
+    }
+
+    #[test]
+    fn finds_panicked_parents_of_subtest() {
+        let mut registry = TestRegistry::default();
+        let events = vec![
+            GoTestEvent {
+                action: GoTestAction::Run,
+                package: "example".to_string(),
+                test: Some("TestFoo".to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            },
+            GoTestEvent {
+                action: GoTestAction::Run,
+                package: "example".to_string(),
+                test: Some("TestFoo/Sub".to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            },
+            GoTestEvent {
+                action: GoTestAction::Output,
+                package: "example".to_string(),
+                test: Some("TestFoo".to_string()),
+                output: Some("panic: boom\n".to_string()),
+                elapsed: None,
+                ..Default::default()
+            },
+        ];
+        for event in events {
+            registry.apply_event(&event);
+        }
+
+        let leaf = TestId {
+            package: "example".to_string(),
+            name: "TestFoo/Sub".to_string(),
+        };
+        let parents = registry.panicked_parents(&leaf);
+        assert_eq!(parents.len(), 1);
+        assert_eq!(parents[0].0.name, "TestFoo");
+        assert!(parents[0].1.output.contains("panic: boom"));
+    }
+
     #[test]
     fn assigns_output_to_current_test() {
         let mut registry = TestRegistry::default();
@@ -363,6 +1040,7 @@ mod tests {
             test: Some("TestFoo".to_string()),
             output: None,
             elapsed: None,
+            ..Default::default()
         };
         registry.apply_event(&run);
         let output = GoTestEvent {
@@ -371,6 +1049,7 @@ mod tests {
             test: None,
             output: Some("line one\n".to_string()),
             elapsed: None,
+            ..Default::default()
         };
         registry.apply_event(&output);
         let id = TestId {
@@ -390,6 +1069,7 @@ mod tests {
             test: Some("TestFoo".to_string()),
             output: Some("\x1b[31mline one\rline two\n".to_string()),
             elapsed: None,
+            ..Default::default()
         };
         registry.apply_event(&event);
         let id = TestId {
@@ -401,6 +1081,7 @@ mod tests {
         assert!(!case.output.contains("\r"));
         assert!(case.output.contains("line one"));
         assert!(case.output.contains("line two"));
+        assert_eq!(case.raw_output, "\x1b[31mline one\rline two\n");
     }
 
     #[test]
@@ -412,6 +1093,7 @@ mod tests {
             test: Some("TestFoo".to_string()),
             output: None,
             elapsed: None,
+            ..Default::default()
         };
         registry.apply_event(&run);
         let output = GoTestEvent {
@@ -420,6 +1102,7 @@ mod tests {
             test: None,
             output: Some("PASS\n".to_string()),
             elapsed: None,
+            ..Default::default()
         };
         registry.apply_event(&output);
         let id = TestId {
@@ -430,6 +1113,77 @@ mod tests {
         assert!(!case.output.contains("PASS"));
     }
 
+    #[test]
+    fn records_package_failure_without_failing_tests() {
+        let mut registry = TestRegistry::default();
+        let events = vec![
+            GoTestEvent {
+                action: GoTestAction::Run,
+                package: "example".to_string(),
+                test: Some("TestFoo".to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            },
+            GoTestEvent {
+                action: GoTestAction::Pass,
+                package: "example".to_string(),
+                test: Some("TestFoo".to_string()),
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            },
+            GoTestEvent {
+                action: GoTestAction::Output,
+                package: "example".to_string(),
+                test: None,
+                output: Some("cleanup failed: db still open\n".to_string()),
+                elapsed: None,
+                ..Default::default()
+            },
+            GoTestEvent {
+                action: GoTestAction::Output,
+                package: "example".to_string(),
+                test: None,
+                output: Some("FAIL\texample\t0.01s\n".to_string()),
+                elapsed: None,
+                ..Default::default()
+            },
+            GoTestEvent {
+                action: GoTestAction::Fail,
+                package: "example".to_string(),
+                test: None,
+                output: None,
+                elapsed: None,
+                ..Default::default()
+            },
+        ];
+        for event in events {
+            registry.apply_event(&event);
+        }
+
+        let id = TestId {
+            package: "example".to_string(),
+            name: PACKAGE_TEARDOWN_TEST.to_string(),
+        };
+        let case = registry.case(&id).unwrap();
+        assert_eq!(case.status, TestStatus::Failed);
+        assert!(case.output.contains("cleanup failed"));
+        assert!(!case.output.contains("FAIL"));
+        assert!(registry.failed_tests().contains(&id));
+
+        registry.apply_event(&GoTestEvent {
+            action: GoTestAction::Pass,
+            package: "example".to_string(),
+            test: None,
+            output: None,
+            elapsed: None,
+            ..Default::default()
+        });
+        assert!(registry.case(&id).is_none());
+        assert!(!registry.leaf_tests().contains(&id));
+    }
+
     #[test]
     fn finalizes_running_tests_on_package_completion() {
         let mut registry = TestRegistry::default();
@@ -449,4 +1203,145 @@ mod tests {
         let case = registry.case(&id).unwrap();
         assert_eq!(case.status, TestStatus::Passed);
     }
+
+    #[test]
+    fn skipped_tests_are_not_failures() {
+        let mut registry = TestRegistry::default();
+        registry.apply_event(&GoTestEvent {
+            action: GoTestAction::Skip,
+            package: "example".to_string(),
+            test: Some("TestSkip".to_string()),
+            output: None,
+            elapsed: Some(0.0),
+            ..Default::default()
+        });
+        let id = TestId {
+            package: "example".to_string(),
+            name: "TestSkip".to_string(),
+        };
+        assert_eq!(registry.case(&id).unwrap().status, TestStatus::Skipped);
+        assert!(registry.failed_tests().is_empty());
+    }
+
+    #[test]
+    fn pause_stops_routing_output_to_the_paused_test() {
+        let mut registry = TestRegistry::default();
+        let event = |action, test: Option<&str>, output: Option<&str>| GoTestEvent {
+            action,
+            package: "example".to_string(),
+            test: test.map(str::to_string),
+            output: output.map(str::to_string),
+            elapsed: None,
+            ..Default::default()
+        };
+        registry.apply_event(&event(GoTestAction::Run, Some("TestA"), None));
+        registry.apply_event(&event(GoTestAction::Pause, Some("TestA"), None));
+        registry.apply_event(&event(GoTestAction::Output, None, Some("stray\n")));
+        let id = TestId {
+            package: "example".to_string(),
+            name: "TestA".to_string(),
+        };
+        assert!(!registry.case(&id).unwrap().output.contains("stray"));
+
+        registry.apply_event(&event(GoTestAction::Cont, Some("TestA"), None));
+        registry.apply_event(&event(GoTestAction::Output, None, Some("resumed\n")));
+        assert!(registry.case(&id).unwrap().output.contains("resumed"));
+    }
+
+    #[test]
+    fn truncates_output_past_the_cap() {
+        let mut registry = TestRegistry {
+            max_output_bytes: Some(10),
+            ..Default::default()
+        };
+        for line in ["0123456789\n", "dropped\n", "dropped too\n"] {
+            registry.apply_event(&GoTestEvent {
+                action: GoTestAction::Output,
+                package: "example".to_string(),
+                test: Some("TestLoud".to_string()),
+                output: Some(line.to_string()),
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+        let id = TestId {
+            package: "example".to_string(),
+            name: "TestLoud".to_string(),
+        };
+        let case = registry.case(&id).unwrap();
+        assert_eq!(case.output, format!("0123456789\n{}", OUTPUT_TRUNCATED));
+    }
+
+    #[test]
+    fn clear_finished_drops_passed_tests_and_old_output() {
+        let mut registry = TestRegistry::default();
+        for (action, name) in [
+            (GoTestAction::Pass, "TestPass"),
+            (GoTestAction::Fail, "TestFail"),
+            (GoTestAction::Run, "TestRunning"),
+        ] {
+            for action in [action, GoTestAction::Output] {
+                registry.apply_event(&GoTestEvent {
+                    action,
+                    package: "example".to_string(),
+                    test: Some(name.to_string()),
+                    output: Some("log\n".to_string()),
+                    elapsed: None,
+                    ..Default::default()
+                });
+            }
+        }
+        let failed = TestId {
+            package: "example".to_string(),
+            name: "TestFail".to_string(),
+        };
+        registry.case_mut(&failed).unwrap().prev_output = "older run\n".to_string();
+
+        registry.clear_finished();
+
+        let leaves = registry.leaf_tests();
+        let names: Vec<&str> = leaves.iter().map(|id| id.name.as_str()).collect();
+        assert_eq!(names, vec!["TestFail", "TestRunning"]);
+        let case = |name: &str| {
+            let id = TestId {
+                package: "example".to_string(),
+                name: name.to_string(),
+            };
+            registry.case(&id).unwrap().clone()
+        };
+        assert!(case("TestFail").output.is_empty());
+        assert!(case("TestFail").prev_output.is_empty());
+        assert_eq!(case("TestFail").status, TestStatus::Failed);
+        assert!(!case("TestRunning").output.is_empty());
+    }
+
+    #[test]
+    fn marks_tests_replayed_from_cache() {
+        let mut registry = TestRegistry::default();
+        let event = |action, test: Option<&str>, output: Option<&str>| GoTestEvent {
+            action,
+            package: "example".to_string(),
+            test: test.map(str::to_string),
+            output: output.map(str::to_string),
+            elapsed: None,
+            ..Default::default()
+        };
+        let id = TestId {
+            package: "example".to_string(),
+            name: "TestFoo".to_string(),
+        };
+        registry.apply_event(&event(GoTestAction::Run, Some("TestFoo"), None));
+        registry.apply_event(&event(GoTestAction::Pass, Some("TestFoo"), None));
+        let summary = "ok  \texample\t(cached)\n";
+        registry.apply_event(&event(GoTestAction::Output, None, Some(summary)));
+        registry.apply_event(&event(GoTestAction::Pass, None, None));
+        assert!(registry.case(&id).unwrap().cached);
+
+        registry.apply_event(&event(GoTestAction::Run, Some("TestFoo"), None));
+        registry.apply_event(&event(GoTestAction::Pass, Some("TestFoo"), None));
+        let summary = "ok  \texample\t0.012s\n";
+        registry.apply_event(&event(GoTestAction::Output, None, Some(summary)));
+        registry.apply_event(&event(GoTestAction::Pass, None, None));
+        assert!(!registry.case(&id).unwrap().cached);
+    }
 }
@@ -10,16 +10,21 @@ pub struct PackageInfo {
     pub dir: PathBuf,
 }
 
+/// Finds the directory gest runs from: the nearest `go.work` workspace (like
+/// the go command, unless `GOWORK=off`), otherwise the nearest `go.mod`.
 pub fn find_repo_root(start: &Path) -> Option<PathBuf> {
-    let mut current = start.to_path_buf();
-    loop {
-        if current.join("go.mod").exists() {
-            return Some(current);
-        }
-        if !current.pop() {
-            return None;
-        }
-    }
+    let workspaces = std::env::var_os("GOWORK").is_none_or(|value| value != "off");
+    workspaces
+        .then(|| find_upwards(start, "go.work"))
+        .flatten()
+        .or_else(|| find_upwards(start, "go.mod"))
+}
+
+fn find_upwards(start: &Path, file: &str) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(file).exists())
+        .map(Path::to_path_buf)
 }
 
 pub fn cache_dir(root: &Path) -> PathBuf {
@@ -38,15 +43,84 @@ pub fn ensure_cache_dir(root: &Path) -> Result<PathBuf, RunnerError> {
     Ok(dir)
 }
 
-pub fn list_packages(root: &Path) -> Result<Vec<PackageInfo>, RunnerError> {
+/// Name of the checked-out git branch, or `None` outside a git repository or
+/// on a detached `HEAD`.
+pub fn current_branch(root: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(root)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if branch.is_empty() || branch == "HEAD" {
+        return None;
+    }
+    Some(branch)
+}
+
+/// Checks that `go` can be run at all, so a missing toolchain is reported
+/// once at startup rather than as a failed `go list`.
+pub fn check_go_toolchain() -> Result<(), RunnerError> {
     let output = Command::new("go")
-        .arg("list")
+        .arg("version")
+        .output()
+        .map_err(RunnerError::go_spawn)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = format!("go version failed: {}", stderr.trim());
+        return Err(RunnerError::Io(message));
+    }
+    Ok(())
+}
+
+/// Patterns covering every package under `root`: `./...` for a module, or
+/// `<dir>/...` for each module of a `go.work` workspace, whose root usually
+/// isn't a module itself (so `./...` fails there).
+pub fn package_patterns(root: &Path) -> Result<Vec<String>, RunnerError> {
+    if !root.join("go.work").exists() {
+        return Ok(vec!["./...".to_string()]);
+    }
+    module_patterns("go", root)
+}
+
+fn module_patterns(go: &str, root: &Path) -> Result<Vec<String>, RunnerError> {
+    let output = Command::new(go)
+        .args(["list", "-m", "-f", "{{.Dir}}"])
+        .current_dir(root)
+        .output()
+        .map_err(RunnerError::go_spawn)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(RunnerError::GoList(stderr.trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| format!("{}/...", dir))
+        .collect())
+}
+
+pub fn list_packages(
+    root: &Path,
+    patterns: &[String],
+    tags: Option<&str>,
+) -> Result<Vec<PackageInfo>, RunnerError> {
+    let mut cmd = Command::new("go");
+    cmd.arg("list");
+    if let Some(tags) = tags {
+        cmd.arg(format!("-tags={}", tags));
+    }
+    let output = cmd
         .arg("-f")
         .arg("{{.ImportPath}}|{{.Dir}}")
-        .arg("./...")
+        .args(patterns)
         .current_dir(root)
         .output()
-        .map_err(|err| RunnerError::Io(err.to_string()))?;
+        .map_err(RunnerError::go_spawn)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -67,26 +141,149 @@ pub fn list_packages(root: &Path) -> Result<Vec<PackageInfo>, RunnerError> {
             dir: PathBuf::from(dir),
         });
     }
-    packages.sort_by(|a, b| b.dir.as_os_str().len().cmp(&a.dir.as_os_str().len()));
+    packages.sort_by_key(|package| std::cmp::Reverse(package.dir.as_os_str().len()));
     Ok(packages)
 }
 
+/// Keeps packages whose import path matches any of `include` (all of them
+/// when it is empty) and does not match `exclude`.
 pub fn filter_packages(
     packages: &[PackageInfo],
-    pattern: Option<&regex::Regex>,
+    include: &[regex::Regex],
+    exclude: Option<&regex::Regex>,
 ) -> Vec<PackageInfo> {
-    if let Some(regex) = pattern {
-        packages
-            .iter()
-            .filter(|package| regex.is_match(&package.import_path))
-            .cloned()
-            .collect()
-    } else {
-        packages.to_vec()
-    }
+    packages
+        .iter()
+        .filter(|package| {
+            let path = &package.import_path;
+            include.is_empty() || include.iter().any(|regex| regex.is_match(path))
+        })
+        .filter(|package| !exclude.is_some_and(|regex| regex.is_match(&package.import_path)))
+        .cloned()
+        .collect()
 }
 
 pub fn package_for_path<'a>(packages: &'a [PackageInfo], path: &Path) -> Option<&'a PackageInfo> {
     let path = path.canonicalize().ok()?;
     packages.iter().find(|package| path.starts_with(&package.dir))
 }
+
+/// Finds the `*_test.go` file in `dir` that declares the test function of
+/// `test_name`. Subtests resolve to their top-level function.
+pub fn find_test_file(dir: &Path, test_name: &str) -> Option<PathBuf> {
+    let function = test_name.split('/').next().unwrap_or(test_name);
+    let needle = format!("func {}(", function);
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with("_test.go"))
+        })
+        .collect();
+    files.sort();
+    files
+        .into_iter()
+        .find(|path| std::fs::read_to_string(path).is_ok_and(|source| source.contains(&needle)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn enumerates_workspace_modules_as_patterns() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("gest-work-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("api")).unwrap();
+        std::fs::write(root.join("api/go.mod"), "module api\n").unwrap();
+        std::fs::write(root.join("go.work"), "go 1.22\n\nuse ./api\nuse ./db\n").unwrap();
+        assert_eq!(package_patterns(&root.join("api")).unwrap(), vec!["./..."]);
+
+        // Stands in for `go list -m -f {{.Dir}}` run in the workspace.
+        let go = root.join("fake-go");
+        let script = format!(
+            "#!/bin/sh\n[ \"$*\" = 'list -m -f {{{{.Dir}}}}' ] || exit 1\necho {0}/api\necho {0}/db\n",
+            root.display()
+        );
+        std::fs::write(&go, script).unwrap();
+        std::fs::set_permissions(&go, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(
+            module_patterns(go.to_str().unwrap(), &root).unwrap(),
+            vec![
+                format!("{}/api/...", root.display()),
+                format!("{}/db/...", root.display()),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn finds_file_declaring_a_test() {
+        let dir = std::env::temp_dir().join(format!("gest-src-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a_test.go"), "func TestOther(t *testing.T) {}\n").unwrap();
+        std::fs::write(dir.join("b_test.go"), "func TestFoo(t *testing.T) {}\n").unwrap();
+        std::fs::write(dir.join("foo.go"), "func TestFoo(t *testing.T) {}\n").unwrap();
+
+        assert_eq!(
+            find_test_file(&dir, "TestFoo/sub"),
+            Some(dir.join("b_test.go"))
+        );
+        assert_eq!(find_test_file(&dir, "TestMissing"), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn excludes_packages_after_including() {
+        let packages: Vec<PackageInfo> = ["svc/api", "svc/api/generated", "svc/db", "tools"]
+            .iter()
+            .map(|path| PackageInfo {
+                import_path: path.to_string(),
+                dir: PathBuf::from(path),
+            })
+            .collect();
+        let include = regex::Regex::new("^svc/").unwrap();
+        let exclude = regex::Regex::new("generated").unwrap();
+        let paths = |packages: Vec<PackageInfo>| -> Vec<String> {
+            packages
+                .into_iter()
+                .map(|package| package.import_path)
+                .collect()
+        };
+        assert_eq!(
+            paths(filter_packages(&packages, &[include], Some(&exclude))),
+            vec!["svc/api", "svc/db"]
+        );
+        assert_eq!(
+            paths(filter_packages(&packages, &[], Some(&exclude))),
+            vec!["svc/api", "svc/db", "tools"]
+        );
+        assert_eq!(paths(filter_packages(&packages, &[], None)).len(), 4);
+    }
+
+    #[test]
+    fn includes_packages_matching_any_pattern() {
+        let packages: Vec<PackageInfo> = ["api", "db", "tools"]
+            .iter()
+            .map(|path| PackageInfo {
+                import_path: path.to_string(),
+                dir: PathBuf::from(path),
+            })
+            .collect();
+        let include = [
+            regex::Regex::new("^api$").unwrap(),
+            regex::Regex::new("^tools$").unwrap(),
+        ];
+        let kept: Vec<String> = filter_packages(&packages, &include, None)
+            .into_iter()
+            .map(|package| package.import_path)
+            .collect();
+        assert_eq!(kept, vec!["api", "tools"]);
+    }
+}
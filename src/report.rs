@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::model::{TestCase, TestId, TestRegistry, TestStatus};
+
+/// Renders the leaf tests of `registry` as TAP version 13. Subtests are
+/// flattened to `package::Parent/Sub` names; failures carry their captured
+/// output in a YAML diagnostic block.
+pub fn tap_report(registry: &TestRegistry) -> String {
+    let tests = registry.leaf_tests();
+    let mut out = String::new();
+    let _ = writeln!(out, "TAP version 13");
+    let _ = writeln!(out, "1..{}", tests.len());
+    for (index, id) in tests.iter().enumerate() {
+        let number = index + 1;
+        let Some(case) = registry.case(id) else {
+            continue;
+        };
+        match case.status {
+            TestStatus::Passed => {
+                let _ = writeln!(out, "ok {} - {}", number, id);
+            }
+            TestStatus::Skipped => {
+                let _ = writeln!(out, "ok {} - {} # SKIP", number, id);
+            }
+            TestStatus::Unknown => {
+                let _ = writeln!(out, "ok {} - {} # SKIP not run", number, id);
+            }
+            TestStatus::Failed | TestStatus::Running => {
+                let message = if case.status == TestStatus::Failed {
+                    "test failed"
+                } else {
+                    "test did not finish"
+                };
+                let _ = writeln!(out, "not ok {} - {}", number, id);
+                let _ = writeln!(out, "  ---");
+                let _ = writeln!(out, "  message: {:?}", message);
+                if case.panic {
+                    let _ = writeln!(out, "  panic: true");
+                }
+                if !case.output.is_empty() {
+                    let _ = writeln!(out, "  output: |");
+                    for line in case.output.lines() {
+                        let _ = writeln!(out, "    {}", line);
+                    }
+                }
+                let _ = writeln!(out, "  ...");
+            }
+        }
+    }
+    out
+}
+
+/// Renders the leaf tests of `registry` as JUnit XML with one `<testsuite>`
+/// per package. Tests that never ran are reported as skipped and tests still
+/// running at exit as errors.
+pub fn junit_report(registry: &TestRegistry) -> String {
+    let mut packages: BTreeMap<&str, Vec<&TestId>> = BTreeMap::new();
+    let tests = registry.leaf_tests();
+    for id in &tests {
+        packages.entry(id.package.as_str()).or_default().push(id);
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(out, "<testsuites>");
+    for (package, ids) in packages {
+        let cases: Vec<_> = ids
+            .into_iter()
+            .filter_map(|id| registry.case(id).map(|case| (id, case)))
+            .collect();
+        let count = |status| {
+            let matching = cases.iter().filter(|(_, case)| case.status == status);
+            matching.count()
+        };
+        let seconds = |case: &TestCase| case.duration.map_or(0.0, |d| d.as_secs_f64());
+        let time: f64 = cases.iter().map(|(_, case)| seconds(case)).sum();
+        let _ = writeln!(
+            out,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" skipped="{}" time="{:.3}">"#,
+            xml_escape(package),
+            cases.len(),
+            count(TestStatus::Failed),
+            count(TestStatus::Running),
+            count(TestStatus::Skipped) + count(TestStatus::Unknown),
+            time
+        );
+        for (id, case) in cases {
+            let _ = write!(
+                out,
+                r#"    <testcase classname="{}" name="{}" time="{:.3}""#,
+                xml_escape(package),
+                xml_escape(&id.name),
+                seconds(case)
+            );
+            let (element, message) = match case.status {
+                TestStatus::Passed => {
+                    let _ = writeln!(out, "/>");
+                    continue;
+                }
+                TestStatus::Skipped | TestStatus::Unknown => {
+                    let message = match case.status {
+                        TestStatus::Skipped => "skipped",
+                        _ => "not run",
+                    };
+                    let _ = writeln!(out, ">");
+                    let _ = writeln!(out, r#"      <skipped message="{}"/>"#, message);
+                    let _ = writeln!(out, "    </testcase>");
+                    continue;
+                }
+                TestStatus::Failed => ("failure", "test failed"),
+                TestStatus::Running => ("error", "test did not finish"),
+            };
+            let _ = writeln!(out, ">");
+            let _ = writeln!(
+                out,
+                r#"      <{} message="{}">{}</{}>"#,
+                element,
+                message,
+                xml_escape(&case.output),
+                element
+            );
+            let _ = writeln!(out, "    </testcase>");
+        }
+        let _ = writeln!(out, "  </testsuite>");
+    }
+    let _ = writeln!(out, "</testsuites>");
+    out
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab and newline are not valid XML 1.0.
+            _ if ch.is_control() && ch != '\n' && ch != '\t' => {}
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[derive(Serialize)]
+struct JsonTest<'a> {
+    #[serde(flatten)]
+    id: &'a TestId,
+    #[serde(flatten)]
+    case: &'a TestCase,
+}
+
+/// Renders every leaf test of `registry` as a JSON array of objects with the
+/// test id, status, duration in seconds, panic flag and captured output.
+pub fn json_report(registry: &TestRegistry) -> String {
+    let tests = registry.leaf_tests();
+    let entries: Vec<JsonTest> = tests
+        .iter()
+        .filter_map(|id| registry.case(id).map(|case| JsonTest { id, case }))
+        .collect();
+    let mut out = serde_json::to_string_pretty(&entries).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::go::{GoTestAction, GoTestEvent};
+
+    #[test]
+    fn renders_tap_with_failure_diagnostics() {
+        let mut registry = TestRegistry::default();
+        let events = vec![
+            (GoTestAction::Pass, "TestPass", None),
+            (GoTestAction::Output, "TestFail", Some("want 1\ngot 2\n")),
+            (GoTestAction::Fail, "TestFail", None),
+        ];
+        for (action, name, output) in events {
+            registry.apply_event(&GoTestEvent {
+                action,
+                package: "example".to_string(),
+                test: Some(name.to_string()),
+                output: output.map(str::to_string),
+                elapsed: None,
+                ..Default::default()
+            });
+        }
+
+        let tap = tap_report(&registry);
+        assert_eq!(
+            tap,
+            "TAP version 13\n\
+             1..2\n\
+             ok 1 - example::TestPass\n\
+             not ok 2 - example::TestFail\n  \
+             ---\n  \
+             message: \"test failed\"\n  \
+             output: |\n    \
+             want 1\n    \
+             got 2\n  \
+             ...\n"
+        );
+    }
+
+    #[test]
+    fn renders_junit_suites_per_package() {
+        let mut registry = TestRegistry::default();
+        let events = vec![
+            ("b", GoTestAction::Pass, "TestPass", None, Some(0.5)),
+            ("a", GoTestAction::Output, "TestFail", Some("<nil>\n"), None),
+            ("a", GoTestAction::Fail, "TestFail", None, Some(0.25)),
+        ];
+        for (package, action, name, output, elapsed) in events {
+            registry.apply_event(&GoTestEvent {
+                action,
+                package: package.to_string(),
+                test: Some(name.to_string()),
+                output: output.map(str::to_string),
+                elapsed,
+                ..Default::default()
+            });
+        }
+
+        let xml = junit_report(&registry);
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <testsuites>\n  \
+             <testsuite name=\"a\" tests=\"1\" failures=\"1\" errors=\"0\" skipped=\"0\" time=\"0.250\">\n    \
+             <testcase classname=\"a\" name=\"TestFail\" time=\"0.250\">\n      \
+             <failure message=\"test failed\">&lt;nil&gt;\n</failure>\n    \
+             </testcase>\n  \
+             </testsuite>\n  \
+             <testsuite name=\"b\" tests=\"1\" failures=\"0\" errors=\"0\" skipped=\"0\" time=\"0.500\">\n    \
+             <testcase classname=\"b\" name=\"TestPass\" time=\"0.500\"/>\n  \
+             </testsuite>\n\
+             </testsuites>\n"
+        );
+    }
+
+    #[test]
+    fn renders_json_with_status_duration_and_output() {
+        let mut registry = TestRegistry::default();
+        registry.apply_event(&GoTestEvent {
+            action: GoTestAction::Output,
+            package: "example".to_string(),
+            test: Some("TestFail".to_string()),
+            output: Some("boom\n".to_string()),
+            elapsed: None,
+            ..Default::default()
+        });
+        registry.apply_event(&GoTestEvent {
+            action: GoTestAction::Fail,
+            package: "example".to_string(),
+            test: Some("TestFail".to_string()),
+            output: None,
+            elapsed: Some(1.5),
+            ..Default::default()
+        });
+
+        let value: serde_json::Value = serde_json::from_str(&json_report(&registry)).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([{
+                "package": "example",
+                "name": "TestFail",
+                "status": "failed",
+                "output": "boom\n",
+                "panic": false,
+                "duration_secs": 1.5
+            }])
+        );
+    }
+}
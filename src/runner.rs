@@ -1,4 +1,5 @@
-use std::io::{BufRead, BufReader};
+use std::collections::{HashSet, VecDeque};
+use std::io::{BufRead, BufReader, Write};
 use std::process::{Child, Command, Stdio};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
@@ -7,7 +8,7 @@ use std::time::Duration;
 use crossbeam_channel::{Receiver, Sender};
 use thiserror::Error;
 
-use crate::go::{parse_go_test_line, GoTestEvent};
+use crate::go::{build_package, parse_go_test_line, GoTestAction, GoTestEvent};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum RunKind {
@@ -28,13 +29,33 @@ pub struct RunSpec {
     pub kind: RunKind,
     pub packages: Vec<PackageRun>,
     pub no_test_cache_override: Option<bool>,
+    /// Wall-clock limit for the whole run, enforced by cancelling it.
     pub timeout: Option<Duration>,
+    /// Passed to `go test -timeout` for each package in the run.
+    pub go_test_timeout: Option<Duration>,
+    /// Raw `-run` regex for package runs that name no tests (`--run`).
+    pub run_pattern: Option<String>,
 }
 
+/// `RunError` message for a run stopped by `RunnerCommand::Cancel`.
+pub const CANCEL_MESSAGE: &str = "run cancelled";
+
+/// Trailing stderr lines quoted when a package exits non-zero without a
+/// failing test to explain it.
+const STDERR_TAIL_LINES: usize = 20;
+
 #[derive(Debug)]
 pub enum RunnerCommand {
     Run(RunSpec),
-    Cancel { run_id: Option<u64> },
+    Cancel {
+        run_id: Option<u64>,
+    },
+    /// Worker counts for runs started from now on, e.g. once `auto`
+    /// concurrency can be resolved against the discovered packages.
+    SetWorkers {
+        pkg_concurrency: usize,
+        go_test_p: usize,
+    },
     Shutdown,
 }
 
@@ -66,15 +87,58 @@ pub enum RunnerEvent {
         run_id: u64,
         message: String,
     },
+    /// Failing tests of `package` are being run again (`--retries`);
+    /// `attempt` counts from 2 up to `max`.
+    Retry {
+        run_id: u64,
+        package: String,
+        tests: Vec<String>,
+        attempt: u32,
+        max: u32,
+    },
+    /// `--failfast` saw a failing test; packages not yet started are skipped.
+    FailFast {
+        run_id: u64,
+        package: String,
+    },
+    /// A stdout line that was not JSON and could not become a `TestEvent`.
+    UnparsedLine {
+        run_id: u64,
+        line: String,
+    },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct RunnerConfig {
     pub root: std::path::PathBuf,
     pub pkg_concurrency: usize,
     pub go_test_p: usize,
     pub no_test_cache: bool,
     pub test_command: Option<Vec<String>>,
+    /// Build tags passed as `go test -tags`.
+    pub tags: Option<String>,
+    /// Wrapper program passed as `go test -exec`.
+    pub exec: Option<String>,
+    /// Extra environment variables for the test command.
+    pub env: Vec<(String, String)>,
+    /// Build with the race detector (`go test -race`).
+    pub race: bool,
+    /// Report statement coverage (`go test -cover`).
+    pub cover: bool,
+    /// Pass `go test -failfast` and stop starting packages after a failure.
+    pub failfast: bool,
+    /// Rerun failing tests of single-test and selected runs this many times
+    /// before reporting them as failed.
+    pub retries: u32,
+    /// Every raw stdout line of the test command is copied here (`--dump`).
+    pub dump: Option<Arc<Mutex<std::fs::File>>>,
+    /// Run benchmarks matching this pattern instead of tests.
+    pub bench: Option<String>,
+    /// Extra arguments given after `--`, passed after gest's own flags and
+    /// before the packages.
+    pub go_test_args: Vec<String>,
+    /// Default `RunSpec::timeout` for runs started from the UI.
+    pub run_timeout: Option<Duration>,
 }
 
 #[derive(Error, Debug)]
@@ -87,6 +151,78 @@ pub enum RunnerError {
     GoList(String),
     #[error("go test failed: {0}")]
     GoTest(String),
+    #[error("go toolchain not found on PATH")]
+    GoNotFound,
+}
+
+impl RunnerError {
+    /// Maps a failure to start the `go` binary, turning a missing binary into
+    /// `GoNotFound` instead of a bare OS error.
+    pub fn go_spawn(err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound => RunnerError::GoNotFound,
+            _ => RunnerError::Io(err.to_string()),
+        }
+    }
+}
+
+/// Run id of `--replay` events, apart from the ids of real runs.
+pub const REPLAY_RUN_ID: u64 = u64::MAX;
+
+/// Feeds a saved `go test -json` stream through the parser as if it came
+/// from a live run, one line every `delay`.
+pub fn start_replay(lines: Vec<String>, delay: Duration, event_tx: Sender<RunnerEvent>) {
+    std::thread::spawn(move || replay_lines(&lines, delay, &event_tx));
+}
+
+fn replay_lines(lines: &[String], delay: Duration, event_tx: &Sender<RunnerEvent>) {
+    let run_id = REPLAY_RUN_ID;
+    let events: Vec<Option<GoTestEvent>> =
+        lines.iter().map(|line| parse_go_test_line(line)).collect();
+    let packages: HashSet<&str> = events
+        .iter()
+        .flatten()
+        .map(|event| event.package.as_str())
+        .collect();
+    let _ = event_tx.send(RunnerEvent::RunStarted {
+        run_id,
+        kind: RunKind::All,
+        packages: packages.len(),
+    });
+    let mut started = HashSet::new();
+    for (seq, (line, event)) in lines.iter().zip(events.iter().cloned()).enumerate() {
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+        let Some(mut event) = event else {
+            if !line.trim().is_empty() {
+                let line = line.clone();
+                let _ = event_tx.send(RunnerEvent::UnparsedLine { run_id, line });
+            }
+            continue;
+        };
+        event.seq = seq as u64;
+        let package = event.package.clone();
+        if started.insert(package.clone()) {
+            let package = package.clone();
+            let _ = event_tx.send(RunnerEvent::PackageStarted { run_id, package });
+        }
+        let finished =
+            event.test.is_none() && matches!(event.action, GoTestAction::Pass | GoTestAction::Fail);
+        let success = event.action == GoTestAction::Pass;
+        let _ = event_tx.send(RunnerEvent::TestEvent { run_id, event });
+        if finished {
+            let _ = event_tx.send(RunnerEvent::PackageFinished {
+                run_id,
+                package,
+                success,
+            });
+        }
+    }
+    let _ = event_tx.send(RunnerEvent::RunFinished {
+        run_id,
+        kind: RunKind::All,
+    });
 }
 
 pub fn start_runner(
@@ -101,6 +237,10 @@ pub fn start_runner(
 struct ActiveRun {
     run_id: u64,
     cancel: AtomicBool,
+    /// A test failed under `--failfast`; the run is cancelled.
+    failed_fast: AtomicBool,
+    /// Source of `GoTestEvent::seq`, shared by every package of the run.
+    next_seq: AtomicU64,
     completed: AtomicBool,
     error_sent: AtomicBool,
     cancel_reason: Mutex<Option<String>>,
@@ -112,6 +252,8 @@ impl ActiveRun {
         Self {
             run_id,
             cancel: AtomicBool::new(false),
+            failed_fast: AtomicBool::new(false),
+            next_seq: AtomicU64::new(1),
             completed: AtomicBool::new(false),
             error_sent: AtomicBool::new(false),
             cancel_reason: Mutex::new(None),
@@ -119,6 +261,10 @@ impl ActiveRun {
         }
     }
 
+    fn next_seq(&self) -> u64 {
+        self.next_seq.fetch_add(1, Ordering::SeqCst)
+    }
+
     fn is_cancelled(&self) -> bool {
         self.cancel.load(Ordering::SeqCst)
     }
@@ -168,15 +314,17 @@ struct ActiveRunHandle {
 #[derive(Clone)]
 struct PackageContext {
     run_id: u64,
-    root: std::path::PathBuf,
-    go_test_p: usize,
-    no_test_cache: bool,
-    test_command: Option<Vec<String>>,
+    config: RunnerConfig,
+    spec: RunSpec,
     event_tx: Sender<RunnerEvent>,
     active_run: Arc<ActiveRun>,
 }
 
-fn runner_loop(rx: Receiver<RunnerCommand>, config: RunnerConfig, event_tx: Sender<RunnerEvent>) {
+fn runner_loop(
+    rx: Receiver<RunnerCommand>,
+    mut config: RunnerConfig,
+    event_tx: Sender<RunnerEvent>,
+) {
     let next_run_id = Arc::new(AtomicU64::new(1));
     let mut active_run: Option<ActiveRunHandle> = None;
     while let Ok(cmd) = rx.recv() {
@@ -188,7 +336,7 @@ fn runner_loop(rx: Receiver<RunnerCommand>, config: RunnerConfig, event_tx: Send
                         let message = active
                             .run
                             .cancel_message()
-                            .unwrap_or_else(|| "run cancelled".to_string());
+                            .unwrap_or_else(|| CANCEL_MESSAGE.to_string());
                         let _ = event_tx.send(RunnerEvent::RunError { run_id: active.run.run_id, message });
                     }
                     let _ = active.thread.join();
@@ -207,13 +355,13 @@ fn runner_loop(rx: Receiver<RunnerCommand>, config: RunnerConfig, event_tx: Send
             RunnerCommand::Cancel { run_id } => {
                 if let Some(active) = active_run.as_ref() {
                     if run_id.is_none_or(|id| id == active.run.run_id)
-                        && active.run.cancel("run cancelled")
+                        && active.run.cancel(CANCEL_MESSAGE)
                         && active.run.mark_error_sent()
                     {
                         let message = active
                             .run
                             .cancel_message()
-                            .unwrap_or_else(|| "run cancelled".to_string());
+                            .unwrap_or_else(|| CANCEL_MESSAGE.to_string());
                         let _ = event_tx.send(RunnerEvent::RunError {
                             run_id: active.run.run_id,
                             message,
@@ -221,6 +369,13 @@ fn runner_loop(rx: Receiver<RunnerCommand>, config: RunnerConfig, event_tx: Send
                     }
                 }
             }
+            RunnerCommand::SetWorkers {
+                pkg_concurrency,
+                go_test_p,
+            } => {
+                config.pkg_concurrency = pkg_concurrency;
+                config.go_test_p = go_test_p;
+            }
             RunnerCommand::Shutdown => break,
         }
     }
@@ -237,9 +392,6 @@ fn run_spec(
     event_tx: &Sender<RunnerEvent>,
     active_run: Arc<ActiveRun>,
 ) {
-    let no_test_cache = spec
-        .no_test_cache_override
-        .unwrap_or(config.no_test_cache);
     let _ = event_tx.send(RunnerEvent::RunStarted {
         run_id,
         kind: spec.kind,
@@ -272,16 +424,15 @@ fn run_spec(
         let job_rx = job_rx.clone();
         let context = PackageContext {
             run_id,
-            root: config.root.clone(),
-            go_test_p: config.go_test_p,
-            no_test_cache,
-            test_command: config.test_command.clone(),
+            config: config.clone(),
+            spec: spec.clone(),
             event_tx: event_tx.clone(),
             active_run: active_run.clone(),
         };
         handles.push(std::thread::spawn(move || {
             while let Ok(job) = job_rx.recv() {
-                if context.active_run.is_cancelled() {
+                let run = &context.active_run;
+                if run.is_cancelled() || run.failed_fast.load(Ordering::SeqCst) {
                     break;
                 }
                 run_package(&context, job);
@@ -304,7 +455,7 @@ fn run_spec(
     if active_run.is_cancelled() && active_run.mark_error_sent() {
         let message = active_run
             .cancel_message()
-            .unwrap_or_else(|| "run cancelled".to_string());
+            .unwrap_or_else(|| CANCEL_MESSAGE.to_string());
         let _ = event_tx.send(RunnerEvent::RunError { run_id, message });
     }
     let _ = event_tx.send(RunnerEvent::RunFinished {
@@ -331,7 +482,7 @@ fn run_package(context: &PackageContext, job: PackageRun) {
         package: package_label.clone(),
     });
 
-    if job.packages.is_empty() && context.test_command.is_none() {
+    if job.packages.is_empty() && context.config.test_command.is_none() {
         let _ = context.event_tx.send(RunnerEvent::RunError {
             run_id: context.run_id,
             message: "no packages provided for go test".to_string(),
@@ -339,22 +490,78 @@ fn run_package(context: &PackageContext, job: PackageRun) {
         return;
     }
 
-    let mut cmd = build_command(
-        &context.root,
-        context.go_test_p,
-        context.no_test_cache,
-        &job,
-        context.test_command.as_ref(),
-    );
+    let rerun = matches!(context.spec.kind, RunKind::Single | RunKind::Selected);
+    let retries = if rerun && job.tests.is_some() {
+        context.config.retries
+    } else {
+        0
+    };
+    let mut job = job;
+    for attempt in 1..=retries + 1 {
+        let last = attempt > retries;
+        let Some(outcome) = run_attempt(context, &job, &package_label, !last) else {
+            return;
+        };
+        if last || outcome.failed_tests.is_empty() || context.active_run.is_cancelled() {
+            for event in outcome.held {
+                let _ = context.event_tx.send(RunnerEvent::TestEvent {
+                    run_id: context.run_id,
+                    event,
+                });
+            }
+            let _ = context.event_tx.send(RunnerEvent::PackageFinished {
+                run_id: context.run_id,
+                package: package_label,
+                success: outcome.success,
+            });
+            return;
+        }
+        let _ = context.event_tx.send(RunnerEvent::Retry {
+            run_id: context.run_id,
+            package: package_label.clone(),
+            tests: outcome.failed_tests.clone(),
+            attempt: attempt + 1,
+            max: retries + 1,
+        });
+        job.tests = Some(outcome.failed_tests);
+    }
+}
+
+/// What one `go test` invocation of a package produced.
+struct Attempt {
+    success: bool,
+    /// Leaf tests that failed, in the order they failed.
+    failed_tests: Vec<String>,
+    /// `Fail` events held back because the failing tests will be retried.
+    held: Vec<GoTestEvent>,
+}
+
+/// Spawns `job` once and streams its events. With `hold_failures`, `Fail`
+/// events are returned instead of sent so a retry can still turn them into
+/// passes. Returns `None` when the command could not be started.
+fn run_attempt(
+    context: &PackageContext,
+    job: &PackageRun,
+    package_label: &str,
+    hold_failures: bool,
+) -> Option<Attempt> {
+    let mut cmd = build_command(&context.config, &context.spec, job);
 
     let child = match cmd.spawn() {
         Ok(child) => child,
         Err(err) => {
+            let message = match context.config.test_command.as_ref() {
+                Some(command) => format!("failed to spawn {}: {}", command[0], err),
+                None => match RunnerError::go_spawn(err) {
+                    RunnerError::GoNotFound => RunnerError::GoNotFound.to_string(),
+                    err => format!("failed to spawn go test: {}", err),
+                },
+            };
             let _ = context.event_tx.send(RunnerEvent::RunError {
                 run_id: context.run_id,
-                message: format!("failed to spawn go test: {}", err),
+                message,
             });
-            return;
+            return None;
         }
     };
     let child_handle = Arc::new(Mutex::new(child));
@@ -372,19 +579,49 @@ fn run_package(context: &PackageContext, job: PackageRun) {
         let mut guard = child_handle.lock().unwrap();
         guard.stderr.take()
     };
-    if let Some(stderr) = stderr {
+    let stderr_reader = stderr.map(|stderr| {
+        let run_id = context.run_id;
+        let event_tx = context.event_tx.clone();
+        let active_run = context.active_run.clone();
+        let mut package: Option<String> = None;
         std::thread::spawn(move || {
+            // Older go versions print compiler errors here instead of in the
+            // JSON stream, each block headed by `# <package>`. Lines before
+            // the first header belong to no package; they only end up in the
+            // exit message.
             let reader = BufReader::new(stderr);
-            for _ in reader.lines() {
-                // Ignore stderr lines to avoid blocking.
+            let mut tail = VecDeque::new();
+            for line in reader.lines().map_while(Result::ok) {
+                if line.starts_with("# ") {
+                    package = Some(build_package(&line).to_string());
+                }
+                if tail.len() == STDERR_TAIL_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line.clone());
+                let Some(package) = package.clone() else {
+                    continue;
+                };
+                let event = GoTestEvent {
+                    action: GoTestAction::BuildOutput,
+                    package,
+                    test: None,
+                    output: Some(format!("{}\n", line)),
+                    elapsed: None,
+                    seq: active_run.next_seq(),
+                };
+                let _ = event_tx.send(RunnerEvent::TestEvent { run_id, event });
             }
-        });
-    }
+            tail
+        })
+    });
 
     let stdout = {
         let mut guard = child_handle.lock().unwrap();
         guard.stdout.take()
     };
+    let mut failed_tests: Vec<String> = Vec::new();
+    let mut held = Vec::new();
     if let Some(stdout) = stdout {
         let reader = BufReader::new(stdout);
         if !context.active_run.is_cancelled() {
@@ -392,13 +629,52 @@ fn run_package(context: &PackageContext, job: PackageRun) {
                 if context.active_run.is_cancelled() {
                     break;
                 }
+                if let Some(dump) = context.config.dump.as_ref() {
+                    let _ = writeln!(dump.lock().unwrap(), "{}", line);
+                }
                 if let Some(mut event) = parse_go_test_line(&line) {
+                    event.seq = context.active_run.next_seq();
                     if event.package.is_empty() {
-                        event.package = package_label.clone();
+                        event.package = package_label.to_string();
+                    }
+                    let test_failed = event.action == GoTestAction::Fail && event.test.is_some();
+                    // Failures held for a retry may still turn into passes.
+                    if context.config.failfast
+                        && test_failed
+                        && !hold_failures
+                        && !context.active_run.failed_fast.swap(true, Ordering::SeqCst)
+                    {
+                        let _ = context.event_tx.send(RunnerEvent::FailFast {
+                            run_id: context.run_id,
+                            package: event.package.clone(),
+                        });
+                        // A full run is one `go test ./...`, and go's own
+                        // `-failfast` keeps testing the other packages, so stop
+                        // every running child. `FailFast` already explains it.
+                        context.active_run.mark_error_sent();
+                        context.active_run.cancel("failfast");
+                    }
+                    if event.action == GoTestAction::Fail {
+                        if let Some(test) = event.test.as_ref() {
+                            let parent = format!("{}/", test);
+                            let child_failed = |failed: &String| failed.starts_with(&parent);
+                            if !failed_tests.iter().any(child_failed) {
+                                failed_tests.push(test.clone());
+                            }
+                        }
+                        if hold_failures {
+                            held.push(event);
+                            continue;
+                        }
                     }
                     let _ = context
                         .event_tx
                         .send(RunnerEvent::TestEvent { run_id: context.run_id, event });
+                } else if !line.trim().is_empty() {
+                    let _ = context.event_tx.send(RunnerEvent::UnparsedLine {
+                        run_id: context.run_id,
+                        line,
+                    });
                 }
             }
         }
@@ -409,23 +685,49 @@ fn run_package(context: &PackageContext, job: PackageRun) {
         guard.wait().ok()
     };
     let success = status.map(|status| status.success()).unwrap_or(false);
-    let _ = context.event_tx.send(RunnerEvent::PackageFinished {
-        run_id: context.run_id,
-        package: package_label,
+    {
+        let mut guard = context.active_run.children.lock().unwrap();
+        guard.retain(|item| !Arc::ptr_eq(item, &child_handle));
+    }
+    if let Some(status) = status.filter(|status| !status.success()) {
+        if failed_tests.is_empty() && !context.active_run.is_cancelled() {
+            let stderr_tail = stderr_reader
+                .and_then(|reader| reader.join().ok())
+                .unwrap_or_default();
+            let _ = context.event_tx.send(RunnerEvent::RunError {
+                run_id: context.run_id,
+                message: exit_message(package_label, status, &stderr_tail),
+            });
+        }
+    }
+    Some(Attempt {
         success,
-    });
-    let mut guard = context.active_run.children.lock().unwrap();
-    guard.retain(|item| !Arc::ptr_eq(item, &child_handle));
+        failed_tests,
+        held,
+    })
 }
 
-fn build_command(
-    root: &std::path::Path,
-    go_test_p: usize,
-    no_test_cache: bool,
-    job: &PackageRun,
-    test_command: Option<&Vec<String>>,
-) -> Command {
-    let mut cmd = if let Some(command) = test_command {
+/// Why a package failed when none of its tests did: the exit status and the
+/// end of its stderr.
+fn exit_message(
+    package_label: &str,
+    status: std::process::ExitStatus,
+    stderr_tail: &VecDeque<String>,
+) -> String {
+    let mut message = match status.code() {
+        Some(code) => format!("{} exited with code {}", package_label, code),
+        None => format!("{} exited ({})", package_label, status),
+    };
+    if !stderr_tail.is_empty() {
+        message.push_str(":\n");
+        let lines: Vec<&str> = stderr_tail.iter().map(String::as_str).collect();
+        message.push_str(&lines.join("\n"));
+    }
+    message
+}
+
+fn build_command(config: &RunnerConfig, spec: &RunSpec, job: &PackageRun) -> Command {
+    let mut cmd = if let Some(command) = config.test_command.as_ref() {
         let mut cmd = Command::new(
             command
                 .first()
@@ -435,41 +737,138 @@ fn build_command(
         if command.len() > 1 {
             cmd.args(&command[1..]);
         }
+        if let Some(pattern) = run_pattern(spec, job) {
+            cmd.arg("-run").arg(pattern);
+        }
+        cmd.args(&config.go_test_args).args(&job.packages);
         cmd
     } else {
         let mut cmd = Command::new("go");
         cmd.arg("test")
             .arg("-json")
-            .arg(format!("-p={}", go_test_p));
+            .arg(format!("-p={}", config.go_test_p));
 
-        if no_test_cache {
+        if spec.no_test_cache_override.unwrap_or(config.no_test_cache) {
             cmd.arg("-count=1");
         }
 
-        if let Some(tests) = &job.tests {
-            if !tests.is_empty() {
-                let pattern = build_run_regex(tests);
-                cmd.arg("-run").arg(pattern);
-            }
+        if config.race {
+            cmd.arg("-race");
+        }
+
+        if config.cover {
+            cmd.arg("-cover");
+        }
+
+        if config.failfast {
+            cmd.arg("-failfast");
+        }
+
+        if let Some(tags) = config.tags.as_ref() {
+            cmd.arg(format!("-tags={}", tags));
+        }
+
+        if let Some(timeout) = spec.go_test_timeout {
+            cmd.arg(format!("-timeout={}s", timeout.as_secs().max(1)));
         }
 
-        cmd.args(&job.packages);
+        if let Some(exec) = config.exec.as_ref() {
+            cmd.arg(format!("-exec={}", exec));
+        }
+
+        if let Some(bench) = config.bench.as_ref() {
+            let tests = job.tests.as_ref().filter(|tests| !tests.is_empty());
+            let pattern = tests.map(|tests| build_run_regex(tests));
+            cmd.arg("-run=^$")
+                .arg(format!("-bench={}", pattern.as_ref().unwrap_or(bench)));
+        } else if let Some(pattern) = run_pattern(spec, job) {
+            cmd.arg("-run").arg(pattern);
+        }
+
+        cmd.args(&config.go_test_args).args(&job.packages);
         cmd
     };
 
-    cmd.current_dir(root)
+    cmd.current_dir(&config.root)
+        .envs(config.env.iter().map(|(key, value)| (key, value)))
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
     cmd
 }
 
+/// Renders the commands `spec` would spawn, one shell-quoted line per job.
+pub fn command_lines(config: &RunnerConfig, spec: &RunSpec) -> Vec<String> {
+    spec.packages
+        .iter()
+        .map(|job| {
+            let cmd = build_command(config, spec, job);
+            let env = cmd.get_envs().filter_map(|(key, value)| {
+                let (key, value) = (key.to_string_lossy(), value?.to_string_lossy());
+                Some(format!("{}={}", key, value))
+            });
+            let args = std::iter::once(cmd.get_program())
+                .chain(cmd.get_args())
+                .map(|arg| arg.to_string_lossy().into_owned());
+            env.chain(args)
+                .map(|arg| shell_quote(&arg))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "-_./=:,+@%".contains(ch));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// The `-run` regex for `job`: its tests if it names any, otherwise the
+/// run's raw pattern.
+fn run_pattern(spec: &RunSpec, job: &PackageRun) -> Option<String> {
+    match job.tests.as_ref().filter(|tests| !tests.is_empty()) {
+        Some(tests) => Some(build_run_regex(tests)),
+        None => spec.run_pattern.clone(),
+    }
+}
+
+/// Builds a `-run` pattern matching `tests`. Go splits the pattern on `/`
+/// and matches each level of the subtest path separately, so every level
+/// gets its own anchored alternation, e.g. `^(TestFoo)$/^(Sub)$`. With
+/// several tests this can also match siblings named at the same levels.
+/// A deeper level would also filter the subtests of shallower tests, so the
+/// pattern stops at the shallowest test and never matches fewer tests than
+/// asked for.
 fn build_run_regex(tests: &[String]) -> String {
-    let mut parts = Vec::new();
+    let depth = tests
+        .iter()
+        .map(|test| test.split('/').count())
+        .min()
+        .unwrap_or(0);
+    let mut levels: Vec<Vec<String>> = Vec::new();
     for test in tests {
-        parts.push(regex::escape(test));
+        for (level, segment) in test.split('/').take(depth).enumerate() {
+            if levels.len() <= level {
+                levels.push(Vec::new());
+            }
+            let segment = regex::escape(segment);
+            if !levels[level].contains(&segment) {
+                levels[level].push(segment);
+            }
+        }
     }
-    format!("^({})$", parts.join("|"))
+    levels
+        .iter()
+        .map(|level| format!("^({})$", level.join("|")))
+        .collect::<Vec<_>>()
+        .join("/")
 }
 
 #[cfg(test)]
@@ -478,9 +877,178 @@ mod tests {
 
     #[test]
     fn builds_run_regex() {
-        let tests = vec!["TestFoo/Sub".to_string(), "TestBar".to_string()];
+        let tests = vec!["TestFoo/Sub".to_string(), "TestBar/Other".to_string()];
         let regex = build_run_regex(&tests);
-        assert!(regex.contains("TestFoo/Sub"));
-        assert!(regex.contains("TestBar"));
+        assert_eq!(regex, "^(TestFoo|TestBar)$/^(Sub|Other)$");
+        // `TestBar` alone must still run all of its subtests.
+        let tests = vec!["TestFoo/Sub".to_string(), "TestBar".to_string()];
+        assert_eq!(build_run_regex(&tests), "^(TestFoo|TestBar)$");
+        assert_eq!(build_run_regex(&["TestFoo".to_string()]), "^(TestFoo)$");
+        assert_eq!(
+            build_run_regex(&["TestFoo/a.b/c".to_string()]),
+            "^(TestFoo)$/^(a\\.b)$/^(c)$"
+        );
+    }
+
+    #[test]
+    fn passes_flags_before_run_and_packages() {
+        let config = RunnerConfig {
+            root: std::path::PathBuf::from("."),
+            pkg_concurrency: 1,
+            go_test_p: 2,
+            no_test_cache: false,
+            test_command: None,
+            tags: Some("integration".to_string()),
+            exec: Some("sudo -E".to_string()),
+            env: vec![("CGO_ENABLED".to_string(), "0".to_string())],
+            race: true,
+            cover: true,
+            failfast: true,
+            retries: 0,
+            dump: None,
+            bench: None,
+            go_test_args: vec!["-shuffle=on".to_string()],
+            run_timeout: None,
+        };
+        let job = PackageRun {
+            packages: vec!["example".to_string()],
+            tests: Some(vec!["TestFoo".to_string()]),
+        };
+        let spec = RunSpec {
+            kind: RunKind::Single,
+            packages: vec![job.clone()],
+            no_test_cache_override: None,
+            timeout: None,
+            go_test_timeout: Some(Duration::from_secs(6000)),
+            run_pattern: None,
+        };
+        let cmd = build_command(&config, &spec, &job);
+        let args: Vec<String> = cmd
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            args,
+            vec![
+                "test",
+                "-json",
+                "-p=2",
+                "-race",
+                "-cover",
+                "-failfast",
+                "-tags=integration",
+                "-timeout=6000s",
+                "-exec=sudo -E",
+                "-run",
+                "^(TestFoo)$",
+                "-shuffle=on",
+                "example"
+            ]
+        );
+        assert_eq!(
+            command_lines(&config, &spec),
+            vec!["CGO_ENABLED=0 go test -json -p=2 -race -cover -failfast -tags=integration -timeout=6000s '-exec=sudo -E' -run '^(TestFoo)$' -shuffle=on example"]
+        );
+    }
+
+    #[test]
+    fn bench_mode_skips_tests_and_reruns_benchmarks_by_name() {
+        let mut config = RunnerConfig {
+            bench: Some(".".to_string()),
+            ..Default::default()
+        };
+        let mut job = PackageRun {
+            packages: vec!["example".to_string()],
+            tests: None,
+        };
+        let spec = RunSpec {
+            kind: RunKind::All,
+            packages: vec![job.clone()],
+            no_test_cache_override: None,
+            timeout: None,
+            go_test_timeout: None,
+            run_pattern: None,
+        };
+        let args = |config: &RunnerConfig, job: &PackageRun| -> Vec<String> {
+            let cmd = build_command(config, &spec, job);
+            let args = cmd.get_args().skip(3);
+            args.map(|arg| arg.to_string_lossy().into_owned()).collect()
+        };
+        assert_eq!(args(&config, &job), vec!["-run=^$", "-bench=.", "example"]);
+
+        job.tests = Some(vec!["BenchmarkFoo".to_string()]);
+        assert_eq!(
+            args(&config, &job),
+            vec!["-run=^$", "-bench=^(BenchmarkFoo)$", "example"]
+        );
+
+        config.bench = None;
+        assert_eq!(
+            args(&config, &job),
+            vec!["-run", "^(BenchmarkFoo)$", "example"]
+        );
+    }
+
+    #[test]
+    fn custom_test_command_gets_run_regex_and_packages() {
+        let config = RunnerConfig {
+            test_command: Some(vec!["gotestsum".to_string(), "--".to_string()]),
+            race: true,
+            ..Default::default()
+        };
+        let job = PackageRun {
+            packages: vec!["example".to_string()],
+            tests: Some(vec!["TestFoo".to_string()]),
+        };
+        let spec = RunSpec {
+            kind: RunKind::Failing,
+            packages: vec![job.clone()],
+            no_test_cache_override: None,
+            timeout: None,
+            go_test_timeout: None,
+            run_pattern: None,
+        };
+        assert_eq!(
+            command_lines(&config, &spec),
+            vec!["gotestsum -- -run '^(TestFoo)$' example"]
+        );
+    }
+
+    #[test]
+    fn uses_raw_run_pattern_when_no_tests_are_named() {
+        let config = RunnerConfig {
+            root: std::path::PathBuf::from("."),
+            ..Default::default()
+        };
+        let mut job = PackageRun {
+            packages: vec!["./...".to_string()],
+            tests: None,
+        };
+        let spec = RunSpec {
+            kind: RunKind::All,
+            packages: vec![job.clone()],
+            no_test_cache_override: None,
+            timeout: None,
+            go_test_timeout: None,
+            run_pattern: Some("TestAuth".to_string()),
+        };
+        let args = |job: &PackageRun| -> Vec<String> {
+            let cmd = build_command(&config, &spec, job);
+            let args = cmd.get_args().skip(3);
+            args.map(|arg| arg.to_string_lossy().into_owned()).collect()
+        };
+        assert_eq!(args(&job), vec!["-run", "TestAuth", "./..."]);
+
+        job.tests = Some(vec!["TestFoo".to_string()]);
+        assert_eq!(args(&job), vec!["-run", "^(TestFoo)$", "./..."]);
+    }
+
+    #[test]
+    fn reports_missing_go_binary_clearly() {
+        let err = std::io::Error::from(std::io::ErrorKind::NotFound);
+        let err = RunnerError::go_spawn(err);
+        assert_eq!(err.to_string(), "go toolchain not found on PATH");
+        let err = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        assert!(matches!(RunnerError::go_spawn(err), RunnerError::Io(_)));
     }
 }
@@ -1,34 +1,100 @@
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Style};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
-use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap};
+use ratatui::widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap};
 use ratatui::Frame;
 
-use crate::app::{App, RunMode};
-use crate::model::TestStatus;
+use crate::app::{search_matches, App, PresetAction, RunMode, SortOrder, StatusFilter};
+use crate::model::{TestId, TestStatus};
+
+/// Smallest terminal the normal layout is drawn in.
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 10;
 
 pub fn draw(frame: &mut Frame<'_>, app: &App) {
     let area = frame.size();
-    let (top_area, main_area, detail_area) = layout_regions(area, app.detail_open);
+    if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+        draw_too_small(frame, app, area);
+        return;
+    }
+    let status = status_lines(app);
+    let gauge_height = u16::from(app.run_state.running);
+    let top_height = status.len() as u16 + gauge_height + 2;
+    let (top_area, main_area, detail_area) =
+        layout_regions(area, app.detail_open, app.detail_right, top_height);
 
-    draw_top_bar(frame, app, top_area);
+    draw_top_bar(frame, status, top_area);
+    if app.run_state.running {
+        draw_progress_gauge(frame, app, top_area);
+    }
 
     match app.mode {
         RunMode::Selecting => draw_select_list(frame, app, main_area),
+        _ if app.package_view => draw_package_list(frame, app, main_area),
         _ => draw_test_list(frame, app, main_area),
     }
 
     if app.detail_open {
         draw_detail(frame, app, detail_area);
     }
+
+    if app.debug && app.debug_open {
+        draw_debug_overlay(frame, app, area);
+    }
+
+    if app.error_log_open {
+        draw_error_log(frame, app, area);
+    }
+
+    if app.help_open {
+        draw_help(frame, app, area);
+    }
+
+    if app.quit_pending() {
+        draw_quit_confirm(frame, area);
+    }
 }
 
-fn layout_regions(area: Rect, detail_open: bool) -> (Rect, Rect, Rect) {
-    let constraints = if detail_open {
-        vec![Constraint::Length(4), Constraint::Min(5), Constraint::Length(30)]
-    } else {
-        vec![Constraint::Length(4), Constraint::Min(5), Constraint::Length(0)]
-    };
+fn draw_too_small(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    // Nothing clickable is on screen.
+    app.list_area.set(Rect::default());
+    let message = format!("terminal too small (need {}x{})", MIN_WIDTH, MIN_HEIGHT);
+    let height = (message.len() as u16).div_ceil(area.width.max(1));
+    let paragraph = Paragraph::new(message)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    frame.render_widget(paragraph, centered_rect(area, area.width, height));
+}
+
+/// Splits the screen into top bar, test list and output pane. The output pane
+/// is a band below the list, or a column right of it when `detail_right`.
+fn layout_regions(
+    area: Rect,
+    detail_open: bool,
+    detail_right: bool,
+    top_height: u16,
+) -> (Rect, Rect, Rect) {
+    if detail_right {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(top_height), Constraint::Min(5)])
+            .split(area);
+        let detail_share = if detail_open { 50 } else { 0 };
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(100 - detail_share),
+                Constraint::Percentage(detail_share),
+            ])
+            .split(rows[1]);
+        return (rows[0], columns[0], columns[1]);
+    }
+    let detail_height = if detail_open { 30 } else { 0 };
+    let constraints = vec![
+        Constraint::Length(top_height),
+        Constraint::Min(5),
+        Constraint::Length(detail_height),
+    ];
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(constraints)
@@ -36,7 +102,12 @@ fn layout_regions(area: Rect, detail_open: bool) -> (Rect, Rect, Rect) {
     (chunks[0], chunks[1], chunks[2])
 }
 
-fn draw_top_bar(frame: &mut Frame<'_>, app: &App, area: Rect) {
+/// Packages listed by name in the "in flight" line before "+N more".
+const ACTIVE_PACKAGES_SHOWN: usize = 3;
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+fn status_lines(app: &App) -> Vec<Line<'_>> {
     let mode = match app.mode {
         RunMode::All => "all",
         RunMode::Failing => "failing",
@@ -45,58 +116,310 @@ fn draw_top_bar(frame: &mut Frame<'_>, app: &App, area: Rect) {
     };
     let (done, total) = app.test_progress();
     let progress = if app.run_state.running {
-        format!("running | tests {}/{}", done, total)
+        let spinner = SPINNER[app.spinner_frame % SPINNER.len()];
+        format!("{} running | tests {}/{}", spinner, done, total)
+    } else if let Some(duration) = app.run_duration() {
+        let secs = duration.as_secs_f64();
+        format!("done in {:.1}s | tests {}/{}", secs, done, total)
     } else {
         format!("idle | tests {}/{}", done, total)
     };
-    let line1 = Line::from(vec![
+    let mut spans = vec![
         Span::styled("gest", Style::default().fg(Color::Cyan)),
         Span::raw(" | mode: "),
         Span::raw(mode),
         Span::raw(" | "),
         Span::raw(progress),
-    ]);
+        Span::raw(if app.watch_enabled {
+            " | watch: on"
+        } else {
+            " | watch: off"
+        }),
+    ];
+    let counts = app.status_counts();
+    let mut tallies = vec![
+        (counts.passed, "passed", TestStatus::Passed),
+        (counts.failed, "failed", TestStatus::Failed),
+        (counts.running, "running", TestStatus::Running),
+    ];
+    if counts.skipped > 0 {
+        tallies.push((counts.skipped, "skipped", TestStatus::Skipped));
+    }
+    if counts.unknown > 0 {
+        tallies.push((counts.unknown, "not run", TestStatus::Unknown));
+    }
+    for (index, (count, label, status)) in tallies.into_iter().enumerate() {
+        spans.push(Span::raw(if index == 0 { " | " } else { ", " }));
+        spans.push(Span::styled(
+            format!("{} {}", count, label),
+            Style::default().fg(status_label(status).1),
+        ));
+    }
+    if app.mode == RunMode::All && app.status_filter != StatusFilter::All {
+        spans.push(Span::styled(
+            format!(" | filter: {}", app.status_filter.label()),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if app.mode == RunMode::All && app.sort_order != SortOrder::Status {
+        spans.push(Span::raw(format!(" | sort: {}", app.sort_order.label())));
+    }
+    if let Some(package) = app.package_focus.as_ref() {
+        spans.push(Span::raw(format!(" | package: {}", package)));
+    }
+    if app.detail_right {
+        spans.push(Span::raw(" | output: right"));
+    }
+    if app.discovering_packages {
+        spans.push(Span::styled(
+            " | discovering packages…",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if let Some(include) = app.package_include.as_ref() {
+        spans.push(Span::raw(format!(" | packages: {}", include)));
+    }
+    if let Some(exclude) = app.package_exclude.as_ref() {
+        spans.push(Span::raw(format!(" | excluding: {}", exclude)));
+    }
+    if app.test_all {
+        spans.push(Span::raw(" | packages: all (module + deps)"));
+    }
+    if let Some(pattern) = app.run_pattern.as_ref() {
+        spans.push(Span::raw(format!(" | run: {}", pattern)));
+    }
+    if let Some(bench) = app.runner_config.bench.as_ref() {
+        spans.push(Span::raw(format!(" | bench: {}", bench)));
+    }
+    if app.runner_config.race {
+        spans.push(Span::raw(" | race"));
+    }
+    if let Some(exec) = app.runner_config.exec.as_ref() {
+        spans.push(Span::raw(format!(" | exec: {}", exec)));
+    }
+    if let (true, Some((attempt, max))) = (app.run_state.running, app.run_state.retry) {
+        spans.push(Span::styled(
+            format!(" | retry {}/{}", attempt, max),
+            Style::default().fg(Color::Magenta),
+        ));
+    }
+    if app.run_state.cancelled {
+        spans.push(Span::styled(
+            " | run cancelled",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if let Some(package) = app.run_state.failed_fast.as_ref() {
+        spans.push(Span::styled(
+            format!(" | failfast: stopped after a failure in {}", package),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    if app.loop_until_green {
+        spans.push(Span::styled(
+            " | looping until green (any key stops)",
+            Style::default().fg(Color::Magenta),
+        ));
+    }
+    if app.follow_failures {
+        spans.push(Span::styled(
+            " | following failures",
+            Style::default().fg(Color::Red),
+        ));
+    }
+    if app.debug {
+        spans.push(Span::raw(format!(" | unparsed: {}", app.unparsed_lines)));
+    }
+    let line1 = Line::from(spans);
 
-    let (line2, line3) = match app.mode {
-        RunMode::All => (
-            "keys: a all, o failing, p select, r rerun, R no-cache",
-            "keys: enter toggle output, left close, right open, up/down move, q or ctrl+c quit",
-        ),
-        RunMode::Failing | RunMode::Selected => (
-            "keys: a all, o failing, p select, r rerun, R no-cache, x remove",
-            "keys: enter toggle output, left close, right open, up/down move, q or ctrl+c quit",
-        ),
-        RunMode::Selecting => (
-            "keys: type filter, enter/space toggle, p or esc done",
-            "keys: a all, o failing, up/down move, ctrl+c quit",
-        ),
+    let keys = match app.mode {
+        RunMode::All => "keys: a all, o failing, p select, r rerun, enter output, ? help, q quit",
+        RunMode::Failing | RunMode::Selected => {
+            "keys: a all, o failing, p select, r rerun, x remove, enter output, ? help, q quit"
+        }
+        RunMode::Selecting => {
+            "keys: type filter, enter/space toggle, ctrl+enter run matches, p or esc done"
+        }
     };
-    let mut lines = vec![line1, Line::from(vec![Span::raw(line2)]), Line::from(vec![Span::raw(line3)])];
-    if let Some(error) = app.last_error.as_ref() {
+    let mut lines = vec![line1, Line::from(vec![Span::raw(keys)])];
+    let active = &app.run_state.active_packages;
+    if app.run_state.running && !active.is_empty() {
+        let mut shown = active[..active.len().min(ACTIVE_PACKAGES_SHOWN)].join(", ");
+        if active.len() > ACTIVE_PACKAGES_SHOWN {
+            shown.push_str(&format!(" +{} more", active.len() - ACTIVE_PACKAGES_SHOWN));
+        }
         lines.push(Line::from(vec![
-            Span::styled("error: ", Style::default().fg(Color::Red)),
-            Span::raw(error),
+            Span::styled("in flight: ", Style::default().fg(Color::Yellow)),
+            Span::raw(shown),
         ]));
     }
+    if !app.muted_packages.is_empty() {
+        let mut muted: Vec<&str> = app.muted_packages.iter().map(String::as_str).collect();
+        muted.sort_unstable();
+        lines.push(Line::from(vec![
+            Span::styled("muted: ", Style::default().fg(Color::DarkGray)),
+            Span::raw(format!("{} (M to unmute)", muted.join(", "))),
+        ]));
+    }
+    if app.test_all {
+        lines.push(Line::from(vec![
+            Span::styled("warning: ", Style::default().fg(Color::Yellow)),
+            Span::raw("go test all includes every dependency and can take a long time"),
+        ]));
+    }
+    if let Some(prompt) = app.preset_prompt.as_ref() {
+        let label = match prompt.action {
+            PresetAction::Save => "save selection as preset: ".to_string(),
+            PresetAction::Load => {
+                let mut names: Vec<&str> = app.presets.keys().map(String::as_str).collect();
+                names.sort();
+                format!("load preset ({}): ", names.join(", "))
+            }
+        };
+        lines.push(Line::from(vec![
+            Span::styled(label, Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{}_", prompt.name)),
+        ]));
+    }
+    if let Some(info) = app.info.as_ref() {
+        lines.push(Line::from(vec![
+            Span::styled("info: ", Style::default().fg(Color::Cyan)),
+            Span::raw(info),
+        ]));
+    }
+    if let Some(error) = app.last_error() {
+        // Multi-line errors (e.g. a stderr tail) only show their first line
+        // here; the error log has the rest.
+        let (first, rest) = error.split_once('\n').unwrap_or((error, ""));
+        let mut spans = vec![
+            Span::styled("error: ", Style::default().fg(Color::Red)),
+            Span::raw(first),
+        ];
+        if app.errors.len() > 1 {
+            spans.push(Span::styled(
+                format!(" (+{} earlier, E to view)", app.errors.len() - 1),
+                Style::default().fg(Color::DarkGray),
+            ));
+        } else if !rest.is_empty() {
+            spans.push(Span::styled(
+                " (E to view)",
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines
+}
 
+fn draw_top_bar(frame: &mut Frame<'_>, lines: Vec<Line<'_>>, area: Rect) {
     let block = Block::default().borders(Borders::ALL).title("status");
     let paragraph = Paragraph::new(Text::from(lines)).block(block);
     frame.render_widget(paragraph, area);
 }
 
+/// Package completion of the current run, on the last row inside the top bar.
+fn draw_progress_gauge(frame: &mut Frame<'_>, app: &App, top_area: Rect) {
+    let run = &app.run_state;
+    let ratio = if run.packages_total == 0 {
+        0.0
+    } else {
+        (run.packages_done as f64 / run.packages_total as f64).min(1.0)
+    };
+    let area = Rect {
+        x: top_area.x + 1,
+        y: top_area.y + top_area.height.saturating_sub(2),
+        width: top_area.width.saturating_sub(2),
+        height: 1,
+    };
+    let gauge = Gauge::default()
+        .gauge_style(Style::default().fg(Color::Cyan).bg(Color::Black))
+        .ratio(ratio)
+        .label(format!(
+            "packages {}/{}",
+            run.packages_done, run.packages_total
+        ));
+    frame.render_widget(gauge, area);
+}
+
 fn draw_test_list(frame: &mut Frame<'_>, app: &App, area: Rect) {
-    let tests = app.visible_tests();
-    let items: Vec<ListItem> = tests
+    let tests = app.rows();
+    let (start, end) = visible_window(
+        tests.len(),
+        app.list_state.selected(),
+        app.list_offset.get(),
+        area,
+    );
+    app.list_area.set(area);
+    app.list_offset.set(start);
+    // Prefix rows with their package only when the list spans several.
+    let first_package = tests.first().map(|test| test.package.as_str());
+    let mixed = tests
+        .iter()
+        .any(|test| Some(test.package.as_str()) != first_package);
+    let full = app.flip_package_paths;
+    let package_width = if mixed {
+        tests[start..end]
+            .iter()
+            .map(|test| app.package_label(&test.package, full).chars().count())
+            .max()
+    } else {
+        None
+    };
+    let parents = app.parent_statuses();
+    let items: Vec<ListItem> = tests[start..end]
         .iter()
         .map(|test| {
             let case = app.registry.case(test);
-            let status = case.map(|case| case.status).unwrap_or(TestStatus::Unknown);
-            let (label, color) = status_label(status);
-            let spans = vec![
+            let (label, color) = status_label(app.row_status(test, &parents));
+            let mut duration = case
+                .and_then(|case| case.elapsed())
+                .map(|elapsed| format!("{:.2}s", elapsed.as_secs_f64()))
+                .unwrap_or_default();
+            if case.is_some_and(|case| case.cached) {
+                duration.push_str(" (cached)");
+            }
+            let mut name = tree_label(app, test);
+            if let Some(width) = package_width {
+                let package = app.package_label(&test.package, full);
+                name = format!("{:<width$} {}", package, name);
+            }
+            let race = if case.is_some_and(|case| case.race) {
+                "RACE "
+            } else {
+                ""
+            };
+            let stuck = if app.stuck.contains(test) {
+                "STUCK "
+            } else {
+                ""
+            };
+            let history = history_spans(app.history.get(test));
+            let history_width = history.len() + usize::from(!history.is_empty());
+            let name_width = (area.width as usize)
+                .saturating_sub(
+                    2 + 6 + race.len() + stuck.len() + history_width + duration.len() + 1,
+                )
+                .max(name.chars().count());
+            let flaky = if app.registry.is_flaky(test) {
+                Span::styled("~", Style::default().fg(Color::Magenta))
+            } else {
+                Span::raw(" ")
+            };
+            let mut spans = vec![
                 Span::styled(format!("{:4}", label), Style::default().fg(color)),
                 Span::raw(" "),
-                Span::raw(test.name.clone()),
+                flaky,
+                Span::raw(format!("{:<width$}", name, width = name_width)),
+                Span::raw(" "),
+                Span::styled(race, Style::default().fg(Color::Red)),
+                Span::styled(stuck, Style::default().fg(Color::Yellow)),
             ];
+            if !history.is_empty() {
+                spans.extend(history);
+                spans.push(Span::raw(" "));
+            }
+            spans.push(Span::styled(duration, Style::default().fg(Color::DarkGray)));
             ListItem::new(Line::from(spans))
         })
         .collect();
@@ -104,61 +427,503 @@ fn draw_test_list(frame: &mut Frame<'_>, app: &App, area: Rect) {
     let list = List::new(items)
         .block(Block::default().borders(Borders::ALL).title("tests"))
         .highlight_style(Style::default().bg(Color::DarkGray));
-    let mut list_state = app.list_state.clone();
+    let mut list_state = windowed_state(app, start);
+    frame.render_stateful_widget(list, area, &mut list_state);
+}
+
+/// `✓✗` sparkline of a test's recent results, or nothing unless it has both
+/// passed and failed.
+fn history_spans(results: Option<&Vec<bool>>) -> Vec<Span<'static>> {
+    let Some(results) = results else {
+        return Vec::new();
+    };
+    if !(results.contains(&true) && results.contains(&false)) {
+        return Vec::new();
+    }
+    results
+        .iter()
+        .map(|&passed| {
+            if passed {
+                Span::styled("✓", Style::default().fg(Color::Green))
+            } else {
+                Span::styled("✗", Style::default().fg(Color::Red))
+            }
+        })
+        .collect()
+}
+
+/// Test name indented by its depth in the subtest tree. Parents get a
+/// `▶`/`▼` marker and subtests only show the part below their parent.
+fn tree_label(app: &App, test: &TestId) -> String {
+    let ancestors = app.ancestors(test);
+    let name = match ancestors.last() {
+        Some(parent) => &test.name[parent.name.len() + 1..],
+        None => test.name.as_str(),
+    };
+    let marker = if !app.registry.is_parent(test) {
+        ""
+    } else if app.expanded.contains(test) {
+        "▼ "
+    } else {
+        "▶ "
+    };
+    format!("{}{}{}", "  ".repeat(ancestors.len()), marker, name)
+}
+
+fn draw_package_list(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let packages = app.package_summaries();
+    let selected = app
+        .package_list_state
+        .selected()
+        .map(|index| index.min(packages.len().saturating_sub(1)));
+    let (start, end) = visible_window(packages.len(), selected, app.list_offset.get(), area);
+    app.list_area.set(area);
+    app.list_offset.set(start);
+    let full = !app.flip_package_paths;
+    let items: Vec<ListItem> = packages[start..end]
+        .iter()
+        .map(|summary| {
+            let package = app.package_label(&summary.package, full);
+            let (label, color) = if summary.muted {
+                ("MUTE", Color::DarkGray)
+            } else {
+                status_label(summary.status)
+            };
+            let counts = summary.counts;
+            let mut tally = format!("{} passed, {} failed", counts.passed, counts.failed);
+            if counts.running > 0 {
+                tally.push_str(&format!(", {} running", counts.running));
+            }
+            if counts.skipped > 0 {
+                tally.push_str(&format!(", {} skipped", counts.skipped));
+            }
+            if let Some(duration) = summary.duration {
+                tally.push_str(&format!(" in {:.2}s", duration.as_secs_f64()));
+            }
+            let name_width = (area.width as usize)
+                .saturating_sub(2 + 5 + tally.len() + 1)
+                .max(package.chars().count());
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:4}", label), Style::default().fg(color)),
+                Span::raw(" "),
+                Span::raw(format!("{:<width$}", package, width = name_width)),
+                Span::raw(" "),
+                Span::styled(tally, Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("packages"))
+        .highlight_style(Style::default().bg(Color::DarkGray));
+    let mut list_state = ListState::default();
+    list_state.select(selected.map(|index| index - start));
     frame.render_stateful_widget(list, area, &mut list_state);
 }
 
 fn draw_select_list(frame: &mut Frame<'_>, app: &App, area: Rect) {
-    let tests = app.selection.filtered.clone();
-    let items: Vec<ListItem> = tests
+    let tests = &app.selection.filtered;
+    let (start, end) = visible_window(
+        tests.len(),
+        app.list_state.selected(),
+        app.list_offset.get(),
+        area,
+    );
+    app.list_area.set(area);
+    app.list_offset.set(start);
+    let items: Vec<ListItem> = tests[start..end]
         .iter()
         .map(|test| {
             let selected = app.selected_set.contains(test);
-            let marker = if selected { "[x]" } else { "[ ]" };
-            let line = Line::from(vec![Span::raw(format!("{} {}", marker, test.name))]);
-            ListItem::new(line)
+            let marker = if selected { "[x] " } else { "[ ] " };
+            let mut spans = vec![Span::raw(marker)];
+            let positions = app.selection.positions.get(test).map(Vec::as_slice);
+            spans.extend(highlight_positions(&test.name, positions));
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
-    let title = format!("select: {}", app.selection.query);
+    let title = format!(
+        "select ({}): {}",
+        app.selection.fuzzy.case.label(),
+        app.selection.query
+    );
     let list = List::new(items)
         .block(Block::default().borders(Borders::ALL).title(title))
         .highlight_style(Style::default().bg(Color::DarkGray));
-    let mut list_state = app.list_state.clone();
+    let mut list_state = windowed_state(app, start);
     frame.render_stateful_widget(list, area, &mut list_state);
 }
 
-fn draw_detail(frame: &mut Frame<'_>, app: &App, area: Rect) {
-    let content = if let Some(test) = app.current_test() {
-        if let Some(case) = app.registry.case(&test) {
-            let mut output = String::new();
-            if case.panic {
-                output.push_str("PANIC DETECTED\n");
+/// Splits `text` into spans, bold and underlined at the char `positions`
+/// the fuzzy query matched.
+fn highlight_positions<'a>(text: &'a str, positions: Option<&[usize]>) -> Vec<Span<'a>> {
+    let Some(positions) = positions.filter(|positions| !positions.is_empty()) else {
+        return vec![Span::raw(text)];
+    };
+    let style = |matched: bool| {
+        if matched {
+            Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            Style::default()
+        }
+    };
+    let mut spans = Vec::new();
+    let mut run_start = 0;
+    let mut run_matched = false;
+    for (index, (offset, _)) in text.char_indices().enumerate() {
+        let is_match = positions.contains(&index);
+        if is_match != run_matched {
+            if offset > run_start {
+                spans.push(Span::styled(&text[run_start..offset], style(run_matched)));
             }
-            output.push_str(&case.output);
-            if output.is_empty() {
-                output = "(no output)".to_string();
+            run_start = offset;
+            run_matched = is_match;
+        }
+    }
+    if run_start < text.len() {
+        spans.push(Span::styled(&text[run_start..], style(run_matched)));
+    }
+    spans
+}
+
+/// Returns the `[start, end)` range of rows that fit in a bordered list
+/// `area`, keeping the previous `offset` unless `selected` moved off screen,
+/// in which case it scrolls just far enough to show it again. Only these
+/// rows are turned into `ListItem`s so huge suites stay cheap to redraw.
+fn visible_window(
+    len: usize,
+    selected: Option<usize>,
+    offset: usize,
+    area: Rect,
+) -> (usize, usize) {
+    let height = (area.height.saturating_sub(2) as usize).max(1);
+    let selected = selected.unwrap_or(0).min(len.saturating_sub(1));
+    let mut start = offset.min(len.saturating_sub(height));
+    if selected < start {
+        start = selected;
+    } else if selected >= start + height {
+        start = selected + 1 - height;
+    }
+    (start, (start + height).min(len))
+}
+
+fn windowed_state(app: &App, start: usize) -> ListState {
+    let mut state = ListState::default();
+    state.select(app.list_state.selected().map(|index| index - start));
+    state
+}
+
+fn draw_detail(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let content = if let Some(test) = app.selected_row() {
+        match app.detail_text(test) {
+            Some(output) if !output.is_empty() => {
+                if app.raw_output {
+                    escape_control_chars(&output)
+                } else {
+                    output
+                }
             }
-            output
-        } else {
-            "(no output)".to_string()
+            _ => "(no output)".to_string(),
         }
     } else {
         "(no test selected)".to_string()
     };
 
+    let mut title = if app.detail_diff {
+        "output (diff against the previous run)".to_string()
+    } else if app.raw_output {
+        "output (raw)".to_string()
+    } else {
+        "output".to_string()
+    };
+    if let Some(test) = app.selected_row() {
+        if let Some(percent) = app.registry.coverage(&test.package) {
+            title.push_str(&format!(" | {} coverage: {:.1}%", test.package, percent));
+        }
+    }
+    if app.tailing() {
+        title.push_str(" | tail");
+    }
+    if app.detail_search_active || !app.detail_search.is_empty() {
+        let cursor = if app.detail_search_active { "_" } else { "" };
+        title.push_str(&format!(
+            " | /{}{} ({} matches)",
+            app.detail_search,
+            cursor,
+            app.detail_matches.len()
+        ));
+    }
+    let border_style = if app.detail_focused {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
     frame.render_widget(Clear, area);
-    let paragraph = Paragraph::new(content)
-        .block(Block::default().borders(Borders::ALL).title("output"))
-        .wrap(Wrap { trim: false });
+    let matches = search_matches(&content, &app.detail_search);
+    let highlight = Style::default().fg(Color::Black).bg(Color::Yellow);
+    let lines: Vec<Line> = content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| match matches.binary_search(&index) {
+            Ok(_) => Line::styled(line, highlight),
+            Err(_) if app.detail_diff => Line::styled(line, diff_line_style(line)),
+            Err(_) => Line::raw(line),
+        })
+        .collect();
+    app.detail_width.set(area.width.saturating_sub(2));
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+    let max_scroll = paragraph
+        .line_count(area.width.saturating_sub(2))
+        .saturating_sub(area.height.saturating_sub(2) as usize)
+        .min(u16::MAX as usize) as u16;
+    app.detail_scroll_max.set(max_scroll);
+    let scroll = if app.tailing() {
+        max_scroll
+    } else {
+        app.detail_scroll.min(max_scroll)
+    };
+    let paragraph = paragraph
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border_style)
+                .title(title),
+        )
+        .scroll((scroll, 0));
     frame.render_widget(paragraph, area);
 }
 
+/// Colors removed lines red and added lines green in the output diff.
+fn diff_line_style(line: &str) -> Style {
+    if line.starts_with("--- ") || line.starts_with("+++ ") {
+        Style::default().fg(Color::DarkGray)
+    } else if line.starts_with('-') {
+        Style::default().fg(Color::Red)
+    } else if line.starts_with('+') {
+        Style::default().fg(Color::Green)
+    } else {
+        Style::default()
+    }
+}
+
+fn draw_debug_overlay(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let run = &app.run_state;
+    let cache_age = app
+        .debug_stats
+        .package_cache_generated_at
+        .and_then(|generated| generated.elapsed().ok())
+        .map(|age| format!("{}s", age.as_secs()))
+        .unwrap_or_else(|| "none".to_string());
+    let lines = vec![
+        format!("run id: {:?}", run.run_id),
+        format!("run kind: {:?}", run.kind),
+        format!("running: {}", run.running),
+        format!("packages: {}/{}", run.packages_done, run.packages_total),
+        format!(
+            "run elapsed: {}",
+            run.run_started_at
+                .map(|start| format!("{:.1}s", start.elapsed().as_secs_f64()))
+                .unwrap_or_else(|| "-".to_string())
+        ),
+        format!(
+            "events last loop: {} app, {} runner",
+            app.debug_stats.app_events_last_loop, app.debug_stats.runner_events_last_loop
+        ),
+        format!(
+            "pending runner events: {}",
+            app.debug_stats.pending_runner_events
+        ),
+        format!("registry entries: {}", app.registry.len()),
+        format!("package cache age: {}", cache_age),
+        format!("unparsed json lines: {}", app.unparsed_lines),
+    ];
+    let text = Text::from(lines.into_iter().map(Line::from).collect::<Vec<_>>());
+    let overlay = centered_rect(area, 60, 14);
+    frame.render_widget(Clear, overlay);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("debug (D to close)");
+    let paragraph = Paragraph::new(text).block(block);
+    frame.render_widget(paragraph, overlay);
+}
+
+fn draw_error_log(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let lines: Vec<Line<'_>> = if app.errors.is_empty() {
+        vec![Line::from("no errors")]
+    } else {
+        let mut lines = Vec::new();
+        for error in app.errors.iter().rev() {
+            let mut messages = error.message.lines();
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:>6} ago  ", format_age(error.at.elapsed())),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(messages.next().unwrap_or_default()),
+            ]));
+            // Continuation lines line up under the first.
+            lines.extend(messages.map(|line| Line::from(format!("{:12}{}", "", line))));
+        }
+        lines
+    };
+    let overlay = centered_rect(area, 90, lines.len() as u16 + 2);
+    frame.render_widget(Clear, overlay);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("errors, newest first (E or esc to close)");
+    frame.render_widget(Paragraph::new(lines).block(block), overlay);
+}
+
+/// Compact age such as `42s`, `3m` or `2h`.
+fn format_age(age: std::time::Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h", secs / 3600),
+    }
+}
+
+/// Every keybinding, grouped by where it applies.
+const HELP: &[(&str, &[(&str, &str)])] = &[
+    (
+        "list (all, failing, selected)",
+        &[
+            ("a / o / p", "all mode / only failing mode / select mode"),
+            ("r / R", "rerun selected test / without cache"),
+            ("P", "rerun the selected test's whole package"),
+            (".", "repeat the last run"),
+            ("x", "remove test from failing/selected list"),
+            ("S / G", "save the selection as / load a named preset"),
+            ("up / down", "move selection"),
+            ("space", "expand / collapse subtests"),
+            ("g", "package view (enter opens a package's tests)"),
+            ("n / N", "next / previous failed test"),
+            ("F", "first failure of the current run"),
+            ("f", "cycle status filter (all mode)"),
+            ("s", "sort by status / slowest first (all mode)"),
+            ("w", "toggle watching for file changes"),
+            ("m / M", "toggle mute of the selected package / unmute all"),
+            ("C", "clear old output and forget passed tests"),
+            ("l", "follow new failures as they arrive"),
+            ("L", "rerun failing tests until they all pass"),
+            ("D", "debug overlay (with --debug)"),
+            ("E", "error log (recent errors, newest first)"),
+            ("i", "swap full import paths and short package names"),
+            ("I", "show the path of gest's cache file"),
+            ("esc", "cancel the current run, keeping its results"),
+            ("?", "toggle this help"),
+            ("click / wheel", "select a test / move selection"),
+            ("q / ctrl+c", "quit (q twice during a run)"),
+        ],
+    ),
+    (
+        "output pane",
+        &[
+            ("enter / double-click", "toggle output pane"),
+            ("right / left", "open and focus / unfocus and close"),
+            ("up / down", "scroll (when focused)"),
+            ("pgup / pgdn", "scroll by a page"),
+            ("v", "toggle raw output"),
+            ("d", "diff against the previous run's output"),
+            ("t", "follow a running test's output (tail)"),
+            ("|", "output pane below / right of the list"),
+            ("y", "copy output to the clipboard"),
+            ("c", "copy the go test command for the test"),
+            ("e", "open the test's source in $EDITOR"),
+            ("/ / n", "search the output / jump to the next match"),
+            ("T", "rerun with a 100m go test timeout"),
+        ],
+    ),
+    (
+        "select mode",
+        &[
+            ("type", "fuzzy filter (pkg:<text> narrows to packages)"),
+            ("enter / space", "toggle selection"),
+            ("ctrl+enter / ctrl+r", "select all matches and run"),
+            ("ctrl+a / ctrl+u", "select / unselect all matches"),
+            ("ctrl+s", "cycle case matching"),
+            ("p / esc", "finish and run selected tests"),
+        ],
+    ),
+];
+
+fn draw_help(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let mut lines = Vec::new();
+    for (index, (section, keys)) in HELP.iter().enumerate() {
+        if index > 0 {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            *section,
+            Style::default().fg(Color::Cyan),
+        )));
+        for (key, action) in keys.iter() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<20}", key), Style::default().fg(Color::Yellow)),
+                Span::raw(*action),
+            ]));
+        }
+    }
+    let overlay = centered_rect(area, 70, lines.len() as u16 + 2);
+    let max_scroll = (lines.len() as u16).saturating_sub(overlay.height.saturating_sub(2));
+    app.help_scroll_max.set(max_scroll);
+    let title = if max_scroll > 0 {
+        "help (? or esc to close, up/down to scroll)"
+    } else {
+        "help (? or esc to close)"
+    };
+    frame.render_widget(Clear, overlay);
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .scroll((app.help_scroll.min(max_scroll), 0));
+    frame.render_widget(paragraph, overlay);
+}
+
+fn draw_quit_confirm(frame: &mut Frame<'_>, area: Rect) {
+    let overlay = centered_rect(area, 44, 3);
+    frame.render_widget(Clear, overlay);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let message = Paragraph::new("run in progress — press q again to quit")
+        .alignment(Alignment::Center)
+        .block(block);
+    frame.render_widget(message, overlay);
+}
+
+fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Makes control characters visible instead of letting them drive the terminal.
+fn escape_control_chars(output: &str) -> String {
+    let mut escaped = String::with_capacity(output.len());
+    for ch in output.chars() {
+        if ch.is_control() && ch != '\n' && ch != '\t' {
+            escaped.extend(ch.escape_default());
+        } else {
+            escaped.push(ch);
+        }
+    }
+    escaped
+}
+
 fn status_label(status: TestStatus) -> (&'static str, Color) {
     match status {
         TestStatus::Passed => ("PASS", Color::Green),
         TestStatus::Running => ("RUN", Color::Yellow),
         TestStatus::Failed => ("FAIL", Color::Red),
+        TestStatus::Skipped => ("SKIP", Color::Blue),
         TestStatus::Unknown => ("----", Color::DarkGray),
     }
 }
@@ -168,44 +933,258 @@ mod tests {
     use super::*;
     use ratatui::backend::TestBackend;
     use ratatui::Terminal;
-    use std::collections::HashSet;
 
-    use crate::app::{RunState, SelectionState};
-    use crate::model::{TestId, TestRegistry};
+    use crate::cache::CacheState;
+    use crate::go::{GoTestAction, GoTestEvent};
+    use crate::model::TestId;
     use crate::repo::PackageInfo;
-    use ratatui::widgets::ListState;
+    use crate::runner::{RunKind, RunnerEvent};
+
+    fn sample_app() -> App {
+        let mut app = App::new(
+            std::path::PathBuf::from("."),
+            vec![PackageInfo {
+                import_path: "example".to_string(),
+                dir: std::path::PathBuf::from("."),
+            }],
+            CacheState::default(),
+            RunMode::All,
+            false,
+            false,
+        );
+        app.registry.ensure_test(&TestId {
+            package: "example".to_string(),
+            name: "TestFoo".to_string(),
+        });
+        app.refresh_lists();
+        app
+    }
 
     #[test]
     fn renders_basic_list() {
         let backend = TestBackend::new(60, 20);
         let mut terminal = Terminal::new(backend).unwrap();
-        let mut registry = TestRegistry::default();
-        let test = TestId {
-            package: "example".to_string(),
-            name: "TestFoo".to_string(),
+        let app = sample_app();
+
+        terminal
+            .draw(|frame| draw(frame, &app))
+            .expect("render should succeed");
+    }
+
+    #[test]
+    fn renders_debug_overlay() {
+        let backend = TestBackend::new(80, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = sample_app();
+        app.debug_open = true;
+        app.unparsed_lines = 3;
+        app.debug_stats.pending_runner_events = 7;
+        let render = |terminal: &mut Terminal<TestBackend>, app: &App| -> String {
+            terminal.draw(|frame| draw(frame, app)).unwrap();
+            let buffer = terminal.backend().buffer();
+            buffer.content().iter().map(|cell| cell.symbol()).collect()
         };
-        registry.ensure_test(&test);
-        let app = App {
-            mode: RunMode::All,
-            registry,
-            failing_set: HashSet::new(),
-            selected_set: HashSet::new(),
-            list_state: ListState::default(),
-            detail_open: false,
-            selection: SelectionState::default(),
-            run_state: RunState::default(),
-            packages: vec![PackageInfo {
-                import_path: "example".to_string(),
-                dir: std::path::PathBuf::from("."),
-            }],
-            package_filter_active: false,
-            repo_root: std::path::PathBuf::from("."),
-            watch_enabled: false,
-            last_error: None,
+        // Without --debug the overlay never shows.
+        assert!(!render(&mut terminal, &app).contains("debug (D to close)"));
+
+        app.debug = true;
+        let text = render(&mut terminal, &app);
+        assert!(text.contains("debug (D to close)"));
+        assert!(text.contains("running: false"));
+        assert!(text.contains("pending runner events: 7"));
+        assert!(text.contains("registry entries: 1"));
+        assert!(text.contains("package cache age: none"));
+        assert!(text.contains("unparsed json lines: 3"));
+    }
+
+    #[test]
+    fn renders_help_overlay() {
+        let backend = TestBackend::new(80, 40);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = sample_app();
+        app.help_open = true;
+
+        terminal
+            .draw(|frame| draw(frame, &app))
+            .expect("render should succeed");
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("copy output to the clipboard"));
+        assert!(!text.contains("ctrl+a / ctrl+u"));
+
+        // The last section is reachable by scrolling.
+        app.help_scroll = u16::MAX;
+        terminal
+            .draw(|frame| draw(frame, &app))
+            .expect("render should succeed");
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("ctrl+a / ctrl+u"));
+        assert!(text.contains("finish and run selected tests"));
+    }
+
+    #[test]
+    fn prefixes_package_when_tests_span_packages() {
+        let backend = TestBackend::new(60, 10);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = sample_app();
+        let render = |terminal: &mut Terminal<TestBackend>, app: &App| -> String {
+            terminal.draw(|frame| draw(frame, app)).unwrap();
+            let buffer = terminal.backend().buffer();
+            buffer.content().iter().map(|cell| cell.symbol()).collect()
         };
+        assert!(!render(&mut terminal, &app).contains("example TestFoo"));
+
+        app.registry.ensure_test(&TestId {
+            package: "example/sub".to_string(),
+            name: "TestFoo".to_string(),
+        });
+        app.refresh_lists();
+        let text = render(&mut terminal, &app);
+        assert!(text.contains("example TestFoo"));
+        assert!(text.contains("sub     TestFoo"));
+    }
+
+    #[test]
+    fn asks_for_a_bigger_terminal() {
+        let backend = TestBackend::new(36, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let app = sample_app();
 
         terminal
             .draw(|frame| draw(frame, &app))
             .expect("render should succeed");
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("terminal too small"));
+        assert!(!text.contains("TestFoo"));
+    }
+
+    #[test]
+    fn shows_package_progress_while_running() {
+        let backend = TestBackend::new(80, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = sample_app();
+        app.run_state.running = true;
+        app.run_state.packages_total = 4;
+        app.run_state.packages_done = 1;
+
+        terminal
+            .draw(|frame| draw(frame, &app))
+            .expect("render should succeed");
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("packages 1/4"));
+
+        // One `./...` job still reports each package go finishes.
+        app.handle_runner_event(RunnerEvent::RunStarted {
+            run_id: 1,
+            kind: RunKind::All,
+            packages: 1,
+        });
+        for (package, action) in [
+            ("example/a", GoTestAction::Pass),
+            ("example/b", GoTestAction::Start),
+        ] {
+            app.handle_runner_event(RunnerEvent::TestEvent {
+                run_id: 1,
+                event: GoTestEvent {
+                    action,
+                    package: package.to_string(),
+                    ..Default::default()
+                },
+            });
+        }
+        terminal
+            .draw(|frame| draw(frame, &app))
+            .expect("render should succeed");
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("packages 1/2"));
+        assert!(text.contains("in flight: example/b"));
+    }
+
+    #[test]
+    fn windows_list_around_selection() {
+        let area = Rect::new(0, 0, 20, 12);
+        assert_eq!(visible_window(100, Some(0), 0, area), (0, 10));
+        assert_eq!(visible_window(100, Some(9), 0, area), (0, 10));
+        assert_eq!(visible_window(100, Some(10), 0, area), (1, 11));
+        assert_eq!(visible_window(100, Some(99), 0, area), (90, 100));
+        assert_eq!(visible_window(3, None, 0, area), (0, 3));
+        assert_eq!(visible_window(0, None, 0, area), (0, 0));
+        // Moving within the window keeps it where it is.
+        assert_eq!(visible_window(100, Some(50), 45, area), (45, 55));
+        assert_eq!(visible_window(100, Some(45), 45, area), (45, 55));
+        // Leaving it scrolls just enough, in either direction.
+        assert_eq!(visible_window(100, Some(44), 45, area), (44, 54));
+        assert_eq!(visible_window(100, Some(55), 45, area), (46, 56));
+        // A list that shrank pulls the window back.
+        assert_eq!(visible_window(12, Some(11), 45, area), (2, 12));
+    }
+
+    #[test]
+    fn places_output_pane_right_of_list() {
+        let area = Rect::new(0, 0, 100, 40);
+        let (top, list, detail) = layout_regions(area, true, true, 4);
+        assert_eq!(top.height, 4);
+        assert_eq!((list.x, list.width), (0, 50));
+        assert_eq!((detail.x, detail.width, detail.height), (50, 50, 36));
+
+        let (_, list, detail) = layout_regions(area, true, false, 4);
+        assert_eq!(list.width, 100);
+        assert_eq!((detail.y, detail.height), (10, 30));
+    }
+
+    #[test]
+    fn escapes_control_chars_in_raw_output() {
+        assert_eq!(
+            escape_control_chars("\x1b[31mred\r\n"),
+            "\\u{1b}[31mred\\r\n"
+        );
+    }
+
+    #[test]
+    fn highlights_fuzzy_match_positions() {
+        let spans = highlight_positions("TestAlpha", Some(&[4, 5, 8]));
+        let parts: Vec<(&str, bool)> = spans
+            .iter()
+            .map(|span| {
+                let bold = span.style.add_modifier.contains(Modifier::BOLD);
+                (span.content.as_ref(), bold)
+            })
+            .collect();
+        assert_eq!(
+            parts,
+            vec![("Test", false), ("Al", true), ("ph", false), ("a", true)]
+        );
+        assert_eq!(highlight_positions("TestAlpha", None).len(), 1);
+    }
+
+    #[test]
+    fn shows_multi_line_errors_in_full_only_in_the_error_log() {
+        let backend = TestBackend::new(80, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = sample_app();
+        app.report_error("example/a exited with code 2:\nsetup failed");
+
+        let screen = |terminal: &mut Terminal<TestBackend>, app: &App| {
+            terminal
+                .draw(|frame| draw(frame, app))
+                .expect("render should succeed");
+            let buffer = terminal.backend().buffer();
+            buffer
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
+        let text = screen(&mut terminal, &app);
+        assert!(text.contains("error: example/a exited with code 2: (E to view)"));
+        assert!(!text.contains("setup failed"));
+
+        app.error_log_open = true;
+        let text = screen(&mut terminal, &app);
+        assert!(text.contains("setup failed"));
     }
 }
@@ -1,13 +1,27 @@
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use crossbeam_channel::{after, Receiver, Sender};
+use crossbeam_channel::{after, never, Receiver, Sender};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
 
 use crate::events::WatchEvent;
 
-pub fn start_watcher(root: PathBuf, event_tx: Sender<WatchEvent>) -> notify::Result<()> {
+/// Keeps the filesystem watcher alive; dropping it stops watching and ends
+/// the watcher thread.
+pub struct WatcherHandle {
+    _watcher: RecommendedWatcher,
+}
+
+/// Watches `paths` (or all of `root` when empty) recursively and reports
+/// changed paths once no new change has arrived for `debounce`.
+pub fn start_watcher(
+    root: PathBuf,
+    paths: &[PathBuf],
+    debounce: Duration,
+    event_tx: Sender<WatchEvent>,
+) -> notify::Result<WatcherHandle> {
     let (raw_tx, raw_rx) = crossbeam_channel::unbounded();
     let mut watcher = RecommendedWatcher::new(
         move |res| {
@@ -15,25 +29,63 @@ pub fn start_watcher(root: PathBuf, event_tx: Sender<WatchEvent>) -> notify::Res
         },
         notify::Config::default(),
     )?;
-    watcher.watch(&root, RecursiveMode::Recursive)?;
+    if paths.is_empty() {
+        watcher.watch(&root, RecursiveMode::Recursive)?;
+    }
+    for path in paths {
+        watcher.watch(path, RecursiveMode::Recursive)?;
+    }
+    let ignored = load_gitignore(&root);
 
+    // The loop ends once the watcher, and with it `raw_tx`, is dropped.
     std::thread::spawn(move || {
-        let _watcher = watcher;
-        watch_loop(raw_rx, event_tx);
+        watch_loop(raw_rx, event_tx, &root, &ignored, debounce);
     });
-    Ok(())
+    Ok(WatcherHandle { _watcher: watcher })
+}
+
+/// Builds the ignore rules from the root `.gitignore`, always skipping
+/// `.git` and gest's own `.gest` cache directory.
+fn load_gitignore(root: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    let _ = builder.add(root.join(".gitignore"));
+    let _ = builder.add_line(None, ".git/");
+    let _ = builder.add_line(None, ".gest/");
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+fn is_ignored(ignored: &Gitignore, root: &Path, path: &Path) -> bool {
+    // Matching panics for paths outside the root (e.g. reached via symlinks).
+    path.starts_with(root)
+        && ignored
+            .matched_path_or_any_parents(path, path.is_dir())
+            .is_ignore()
 }
 
-fn watch_loop(raw_rx: Receiver<notify::Result<Event>>, event_tx: Sender<WatchEvent>) {
+fn watch_loop(
+    raw_rx: Receiver<notify::Result<Event>>,
+    event_tx: Sender<WatchEvent>,
+    root: &Path,
+    ignored: &Gitignore,
+    debounce: Duration,
+) {
     let mut pending = HashSet::new();
-    let debounce = Duration::from_millis(250);
     loop {
+        // Only arm the timer while there is something to flush, so an idle
+        // watcher (or a zero debounce) doesn't spin.
+        let timer = if pending.is_empty() {
+            never()
+        } else {
+            after(debounce)
+        };
         crossbeam_channel::select! {
             recv(raw_rx) -> msg => {
                 match msg {
                     Ok(Ok(event)) => {
                         for path in event.paths {
-                            pending.insert(path);
+                            if !is_ignored(ignored, root, &path) {
+                                pending.insert(path);
+                            }
                         }
                     }
                     Ok(Err(err)) => {
@@ -42,12 +94,90 @@ fn watch_loop(raw_rx: Receiver<notify::Result<Event>>, event_tx: Sender<WatchEve
                     Err(_) => break,
                 }
             }
-            recv(after(debounce)) -> _ => {
-                if !pending.is_empty() {
-                    let paths: Vec<PathBuf> = pending.drain().collect();
-                    let _ = event_tx.send(WatchEvent::FilesChanged(paths));
-                }
+            recv(timer) -> _ => {
+                let paths: Vec<PathBuf> = pending.drain().collect();
+                let _ = event_tx.send(WatchEvent::FilesChanged(paths));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flushes_changes_with_zero_debounce() {
+        let (raw_tx, raw_rx) = crossbeam_channel::unbounded();
+        let (event_tx, event_rx) = crossbeam_channel::unbounded();
+        let root = PathBuf::from("/repo");
+        std::thread::spawn(move || {
+            watch_loop(raw_rx, event_tx, &root, &Gitignore::empty(), Duration::ZERO);
+        });
+        let event = Event::default().add_path(PathBuf::from("/repo/main.go"));
+        raw_tx.send(Ok(event)).unwrap();
+
+        match event_rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+            WatchEvent::FilesChanged(paths) => {
+                assert_eq!(paths, vec![PathBuf::from("/repo/main.go")]);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn stops_watching_when_handle_is_dropped() {
+        let root = std::env::temp_dir().join(format!("gest-watch-stop-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let (event_tx, event_rx) = crossbeam_channel::unbounded();
+        let handle = start_watcher(root.clone(), &[], Duration::ZERO, event_tx).unwrap();
+        drop(handle);
+
+        // The watcher thread held the only sender, so the channel closes.
+        assert!(matches!(
+            event_rx.recv_timeout(Duration::from_secs(5)),
+            Err(crossbeam_channel::RecvTimeoutError::Disconnected)
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn skips_gitignored_trees() {
+        let root = std::env::temp_dir().join(format!("gest-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join(".gitignore"), "node_modules/\n*.gen.go\n").unwrap();
+        let ignored = load_gitignore(&root);
+        let check = |path: &Path| is_ignored(&ignored, &root, path);
+
+        assert!(check(&root.join("node_modules/pkg/index.js")));
+        assert!(check(&root.join("api/types.gen.go")));
+        assert!(check(&root.join(".git/index")));
+        assert!(!check(&root.join("api/types.go")));
+        assert!(!check(Path::new("/elsewhere/node_modules/x")));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn watches_only_the_given_paths() {
+        let root = std::env::temp_dir().join(format!("gest-watch-paths-{}", std::process::id()));
+        let watched = root.join("internal");
+        std::fs::create_dir_all(&watched).unwrap();
+        std::fs::create_dir_all(root.join("assets")).unwrap();
+        let (event_tx, event_rx) = crossbeam_channel::unbounded();
+        let paths = std::slice::from_ref(&watched);
+        let _handle = start_watcher(root.clone(), paths, Duration::ZERO, event_tx).unwrap();
+
+        std::fs::write(root.join("assets/bundle.go"), "package assets\n").unwrap();
+        std::fs::write(watched.join("api.go"), "package internal\n").unwrap();
+
+        match event_rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+            WatchEvent::FilesChanged(paths) => {
+                assert!(!paths.is_empty());
+                assert!(paths.iter().all(|path| path.starts_with(&watched)));
             }
+            other => panic!("unexpected event: {:?}", other),
         }
+        let _ = std::fs::remove_dir_all(&root);
     }
 }
@@ -1,6 +1,6 @@
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 
-use gest::app::{App, RunMode};
+use gest::app::{App, RunMode, EXTENDED_GO_TEST_TIMEOUT};
 use gest::cache::CacheState;
 use gest::model::{TestId, TestStatus};
 use gest::repo::PackageInfo;
@@ -29,6 +29,7 @@ fn rerun_marks_running_and_emits_command() {
         name: "TestFoo".to_string(),
     };
     app.registry.ensure_test(&test);
+    app.refresh_lists();
     app.list_state.select(Some(0));
 
     let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
@@ -79,3 +80,68 @@ fn ignores_stale_runner_events() {
     });
     assert_eq!(app.run_state.packages_done, 0);
 }
+
+#[test]
+fn extended_timeout_rerun_sets_go_test_timeout() {
+    let mut app = build_app();
+    let test = TestId {
+        package: "example".to_string(),
+        name: "TestFoo".to_string(),
+    };
+    app.registry.ensure_test(&test);
+    app.refresh_lists();
+    app.list_state.select(Some(0));
+    app.detail_open = true;
+
+    let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+    let event = Event::Key(KeyEvent::new(KeyCode::Char('T'), KeyModifiers::SHIFT));
+    app.handle_input(event, &runner_tx);
+
+    match runner_rx.recv().expect("runner command sent") {
+        RunnerCommand::Run(spec) => {
+            assert_eq!(spec.kind, RunKind::Single);
+            assert_eq!(spec.go_test_timeout, Some(EXTENDED_GO_TEST_TIMEOUT));
+            assert_eq!(spec.packages[0].tests, Some(vec!["TestFoo".to_string()]));
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn ctrl_enter_selects_filtered_tests_and_runs_them() {
+    let mut app = build_app();
+    for name in ["TestAuthLogin", "TestAuthLogout", "TestBilling"] {
+        app.registry.ensure_test(&TestId {
+            package: "example".to_string(),
+            name: name.to_string(),
+        });
+    }
+
+    let (runner_tx, runner_rx) = crossbeam_channel::unbounded();
+    app.handle_input(
+        Event::Key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE)),
+        &runner_tx,
+    );
+    for ch in "Auth".chars() {
+        app.handle_input(
+            Event::Key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE)),
+            &runner_tx,
+        );
+    }
+    app.handle_input(
+        Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::CONTROL)),
+        &runner_tx,
+    );
+
+    assert_eq!(app.mode, RunMode::Selected);
+    assert_eq!(app.selected_set.len(), 2);
+    match runner_rx.recv().expect("runner command sent") {
+        RunnerCommand::Run(spec) => {
+            assert_eq!(spec.kind, RunKind::Selected);
+            let mut tests = spec.packages[0].tests.clone().unwrap();
+            tests.sort();
+            assert_eq!(tests, vec!["TestAuthLogin", "TestAuthLogout"]);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
@@ -0,0 +1,92 @@
+#![cfg(unix)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use gest::app::{App, RunMode};
+use gest::cache::CacheState;
+use gest::control::{serve_control, start_control_server};
+use gest::repo::PackageInfo;
+use gest::runner::{start_runner, RunnerCommand, RunnerConfig};
+
+#[test]
+fn runs_cancels_and_summarizes_over_the_socket() {
+    let mut app = App::new(
+        std::path::PathBuf::from("."),
+        vec![PackageInfo {
+            import_path: "example".to_string(),
+            dir: std::path::PathBuf::from("."),
+        }],
+        CacheState::default(),
+        RunMode::All,
+        false,
+        false,
+    );
+    let path = std::env::temp_dir().join(format!("gest-serve-{}.sock", std::process::id()));
+    let (command_tx, command_rx) = crossbeam_channel::unbounded();
+    let clients = start_control_server(&path, command_tx).unwrap();
+    let (shutdown_tx, shutdown_rx) = crossbeam_channel::unbounded();
+    let (event_tx, event_rx) = crossbeam_channel::unbounded();
+    // One test passes, then the run hangs until it is cancelled.
+    let script = r#"echo '{"Action":"pass","Package":"example","Test":"TestFoo"}'; exec sleep 30"#;
+    let runner_tx = start_runner(
+        RunnerConfig {
+            root: std::path::PathBuf::from("."),
+            pkg_concurrency: 1,
+            go_test_p: 1,
+            test_command: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                script.to_string(),
+                "sh".to_string(),
+            ]),
+            ..Default::default()
+        },
+        event_tx,
+    );
+
+    let client_path = path.clone();
+    let client = std::thread::spawn(move || {
+        let events = (|| -> std::io::Result<Vec<Value>> {
+            let mut stream = UnixStream::connect(&client_path)?;
+            stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+            let mut lines = BufReader::new(stream.try_clone()?).lines();
+            let mut events = Vec::new();
+            stream.write_all(b"{\"command\":\"run\"}\n")?;
+            while let Some(line) = lines.next().transpose()? {
+                let event: Value = serde_json::from_str(&line)?;
+                events.push(event.clone());
+                if event["event"] == "test" && event["action"] == "pass" {
+                    stream.write_all(b"{\"command\":\"cancel\"}\n")?;
+                }
+                if event["event"] == "summary" {
+                    break;
+                }
+            }
+            Ok(events)
+        })();
+        let _ = shutdown_tx.send(());
+        events
+    });
+
+    serve_control(
+        &mut app,
+        &clients,
+        &command_rx,
+        &runner_tx,
+        &event_rx,
+        &shutdown_rx,
+    );
+    let _ = runner_tx.send(RunnerCommand::Shutdown);
+    let _ = std::fs::remove_file(&path);
+
+    let events = client.join().unwrap().unwrap();
+    assert_eq!(events.first().unwrap()["event"], "run_started");
+    let summary = events.last().unwrap();
+    assert_eq!(summary["event"], "summary");
+    assert_eq!(summary["passed"], 1);
+    assert_eq!(summary["failed"], 0);
+}
@@ -35,7 +35,13 @@ fn long_running_command() -> Vec<String> {
     }
     #[cfg(not(windows))]
     {
-        vec!["sleep".to_string(), "2".to_string()]
+        // The runner appends the package list, which `sh -c` ignores.
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "exec sleep 2".to_string(),
+            "sh".to_string(),
+        ]
     }
 }
 
@@ -49,6 +55,7 @@ fn cancel_unblocks_runner_and_updates_app_state() {
             go_test_p: 1,
             no_test_cache: false,
             test_command: Some(long_running_command()),
+            ..Default::default()
         },
         event_tx,
     );
@@ -62,6 +69,8 @@ fn cancel_unblocks_runner_and_updates_app_state() {
         }],
         no_test_cache_override: None,
         timeout: None,
+        go_test_timeout: None,
+        run_pattern: None,
     };
     let _ = runner_tx.send(RunnerCommand::Run(spec));
 
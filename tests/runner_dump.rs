@@ -0,0 +1,53 @@
+#![cfg(not(windows))]
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use gest::runner::{
+    start_runner, PackageRun, RunKind, RunSpec, RunnerCommand, RunnerConfig, RunnerEvent,
+};
+
+#[test]
+fn dump_copies_raw_output_lines() {
+    let path = std::env::temp_dir().join(format!("gest-dump-{}", std::process::id()));
+    let file = std::fs::File::create(&path).unwrap();
+    let (event_tx, event_rx) = crossbeam_channel::unbounded();
+    let runner_tx = start_runner(
+        RunnerConfig {
+            root: std::path::PathBuf::from("."),
+            pkg_concurrency: 1,
+            go_test_p: 1,
+            dump: Some(Arc::new(Mutex::new(file))),
+            test_command: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                r#"echo '{"Action":"run","Test":"TestFoo"}'; echo 'not json'"#.to_string(),
+                "sh".to_string(),
+            ]),
+            ..Default::default()
+        },
+        event_tx,
+    );
+    let _ = runner_tx.send(RunnerCommand::Run(RunSpec {
+        kind: RunKind::All,
+        packages: vec![PackageRun {
+            packages: vec!["example".to_string()],
+            tests: None,
+        }],
+        no_test_cache_override: None,
+        timeout: None,
+        go_test_timeout: None,
+        run_pattern: None,
+    }));
+    while let Ok(event) = event_rx.recv_timeout(Duration::from_secs(5)) {
+        if matches!(event, RunnerEvent::RunFinished { .. }) {
+            break;
+        }
+    }
+    let _ = runner_tx.send(RunnerCommand::Shutdown);
+
+    let dumped = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let expected = "{\"Action\":\"run\",\"Test\":\"TestFoo\"}\nnot json\n";
+    assert_eq!(dumped, expected);
+}
@@ -0,0 +1,114 @@
+#![cfg(not(windows))]
+
+use std::time::Duration;
+
+use gest::runner::{
+    start_runner, PackageRun, RunKind, RunSpec, RunnerCommand, RunnerConfig, RunnerEvent,
+};
+
+#[test]
+fn reports_exit_code_and_stderr_when_no_test_failed() {
+    let (event_tx, event_rx) = crossbeam_channel::unbounded();
+    let runner_tx = start_runner(
+        RunnerConfig {
+            root: std::path::PathBuf::from("."),
+            pkg_concurrency: 1,
+            go_test_p: 1,
+            test_command: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                "echo 'setup failed' >&2; exit 2".to_string(),
+                "sh".to_string(),
+            ]),
+            ..Default::default()
+        },
+        event_tx,
+    );
+
+    let _ = runner_tx.send(RunnerCommand::Run(RunSpec {
+        kind: RunKind::All,
+        packages: vec![PackageRun {
+            packages: vec!["example/a".to_string()],
+            tests: None,
+        }],
+        no_test_cache_override: None,
+        timeout: None,
+        go_test_timeout: None,
+        run_pattern: None,
+    }));
+
+    let mut errors = Vec::new();
+    while let Ok(event) = event_rx.recv_timeout(Duration::from_secs(5)) {
+        match event {
+            RunnerEvent::RunError { message, .. } => errors.push(message),
+            RunnerEvent::RunFinished { .. } => break,
+            _ => {}
+        }
+    }
+    let _ = runner_tx.send(RunnerCommand::Shutdown);
+
+    assert_eq!(
+        errors,
+        vec!["example/a exited with code 2:\nsetup failed".to_string()]
+    );
+}
+
+#[test]
+fn attributes_stderr_only_to_packages_named_in_headers() {
+    let (event_tx, event_rx) = crossbeam_channel::unbounded();
+    let runner_tx = start_runner(
+        RunnerConfig {
+            root: std::path::PathBuf::from("."),
+            pkg_concurrency: 1,
+            go_test_p: 1,
+            test_command: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                "echo 'go: downloading' >&2; echo '# example/b [example/b.test]' >&2; \
+                 echo 'b.go:1: undefined: x' >&2; exit 1"
+                    .to_string(),
+                "sh".to_string(),
+            ]),
+            ..Default::default()
+        },
+        event_tx,
+    );
+
+    let _ = runner_tx.send(RunnerCommand::Run(RunSpec {
+        kind: RunKind::All,
+        packages: vec![PackageRun {
+            packages: vec!["./...".to_string()],
+            tests: None,
+        }],
+        no_test_cache_override: None,
+        timeout: None,
+        go_test_timeout: None,
+        run_pattern: None,
+    }));
+
+    let mut build_output = Vec::new();
+    while let Ok(event) = event_rx.recv_timeout(Duration::from_secs(5)) {
+        match event {
+            RunnerEvent::TestEvent { event, .. } => {
+                build_output.push((event.package, event.output.unwrap_or_default()));
+            }
+            RunnerEvent::RunFinished { .. } => break,
+            _ => {}
+        }
+    }
+    let _ = runner_tx.send(RunnerCommand::Shutdown);
+
+    assert_eq!(
+        build_output,
+        vec![
+            (
+                "example/b".to_string(),
+                "# example/b [example/b.test]\n".to_string()
+            ),
+            (
+                "example/b".to_string(),
+                "b.go:1: undefined: x\n".to_string()
+            ),
+        ]
+    );
+}
@@ -0,0 +1,117 @@
+#![cfg(not(windows))]
+
+use std::time::Duration;
+
+use gest::runner::{
+    start_runner, PackageRun, RunKind, RunSpec, RunnerCommand, RunnerConfig, RunnerEvent,
+};
+
+#[test]
+fn failfast_skips_packages_after_a_failing_test() {
+    let (event_tx, event_rx) = crossbeam_channel::unbounded();
+    let fail = r#"{"Action":"fail","Package":"example/a","Test":"TestFoo"}"#;
+    let runner_tx = start_runner(
+        RunnerConfig {
+            root: std::path::PathBuf::from("."),
+            pkg_concurrency: 1,
+            go_test_p: 1,
+            failfast: true,
+            test_command: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!("echo '{}'", fail),
+                "sh".to_string(),
+            ]),
+            ..Default::default()
+        },
+        event_tx,
+    );
+
+    let job = |package: &str| PackageRun {
+        packages: vec![package.to_string()],
+        tests: None,
+    };
+    let _ = runner_tx.send(RunnerCommand::Run(RunSpec {
+        kind: RunKind::All,
+        packages: vec![job("example/a"), job("example/b")],
+        no_test_cache_override: None,
+        timeout: None,
+        go_test_timeout: None,
+        run_pattern: None,
+    }));
+
+    let mut started = Vec::new();
+    let mut failed_fast = None;
+    while let Ok(event) = event_rx.recv_timeout(Duration::from_secs(5)) {
+        match event {
+            RunnerEvent::PackageStarted { package, .. } => started.push(package),
+            RunnerEvent::FailFast { package, .. } => failed_fast = Some(package),
+            RunnerEvent::RunFinished { .. } => break,
+            _ => {}
+        }
+    }
+    let _ = runner_tx.send(RunnerCommand::Shutdown);
+
+    assert_eq!(failed_fast.as_deref(), Some("example/a"));
+    assert_eq!(started, vec!["example/a".to_string()]);
+}
+
+#[test]
+fn failfast_stops_a_single_multi_package_job() {
+    let (event_tx, event_rx) = crossbeam_channel::unbounded();
+    let fail = r#"{"Action":"fail","Package":"example/a","Test":"TestFoo"}"#;
+    let pass = r#"{"Action":"pass","Package":"example/b","Test":"TestBar"}"#;
+    let runner_tx = start_runner(
+        RunnerConfig {
+            root: std::path::PathBuf::from("."),
+            pkg_concurrency: 1,
+            go_test_p: 1,
+            failfast: true,
+            // Like `go test ./...`: one process that keeps testing the next
+            // package after a failure.
+            test_command: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!(
+                    "echo '{}'; i=0; while [ $i -lt 30 ]; do sleep 0.1; i=$((i+1)); done; echo '{}'",
+                    fail, pass
+                ),
+                "sh".to_string(),
+            ]),
+            ..Default::default()
+        },
+        event_tx,
+    );
+
+    let started = std::time::Instant::now();
+    let _ = runner_tx.send(RunnerCommand::Run(RunSpec {
+        kind: RunKind::All,
+        packages: vec![PackageRun {
+            packages: vec!["example/a".to_string(), "example/b".to_string()],
+            tests: None,
+        }],
+        no_test_cache_override: None,
+        timeout: None,
+        go_test_timeout: None,
+        run_pattern: None,
+    }));
+
+    let mut failed_fast = None;
+    let mut packages = Vec::new();
+    let mut errors = Vec::new();
+    while let Ok(event) = event_rx.recv_timeout(Duration::from_secs(5)) {
+        match event {
+            RunnerEvent::FailFast { package, .. } => failed_fast = Some(package),
+            RunnerEvent::TestEvent { event, .. } => packages.push(event.package),
+            RunnerEvent::RunError { message, .. } => errors.push(message),
+            RunnerEvent::RunFinished { .. } => break,
+            _ => {}
+        }
+    }
+    let _ = runner_tx.send(RunnerCommand::Shutdown);
+
+    assert_eq!(failed_fast.as_deref(), Some("example/a"));
+    assert_eq!(packages, vec!["example/a".to_string()]);
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
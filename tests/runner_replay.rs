@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use gest::go::GoTestAction;
+use gest::runner::{start_replay, RunnerEvent, REPLAY_RUN_ID};
+
+#[test]
+fn replays_a_saved_json_stream_as_a_run() {
+    let (event_tx, event_rx) = crossbeam_channel::unbounded();
+    let lines = [
+        r#"{"Action":"run","Package":"example/a","Test":"TestFoo"}"#,
+        r#"{"Action":"fail","Package":"example/a","Test":"TestFoo","Elapsed":0.1}"#,
+        "not json",
+        r#"{"Action":"fail","Package":"example/a","Elapsed":0.2}"#,
+    ];
+    start_replay(
+        lines.iter().map(|line| line.to_string()).collect(),
+        Duration::ZERO,
+        event_tx,
+    );
+
+    let mut actions = Vec::new();
+    let mut unparsed = 0;
+    let mut finished_packages = Vec::new();
+    while let Ok(event) = event_rx.recv_timeout(Duration::from_secs(5)) {
+        match event {
+            RunnerEvent::RunStarted {
+                run_id, packages, ..
+            } => {
+                assert_eq!(run_id, REPLAY_RUN_ID);
+                assert_eq!(packages, 1);
+            }
+            RunnerEvent::TestEvent { event, .. } => actions.push(event.action),
+            RunnerEvent::UnparsedLine { .. } => unparsed += 1,
+            RunnerEvent::PackageFinished {
+                package, success, ..
+            } => finished_packages.push((package, success)),
+            RunnerEvent::RunFinished { .. } => break,
+            _ => {}
+        }
+    }
+
+    assert_eq!(
+        actions,
+        vec![GoTestAction::Run, GoTestAction::Fail, GoTestAction::Fail]
+    );
+    assert_eq!(unparsed, 1);
+    assert_eq!(finished_packages, vec![("example/a".to_string(), false)]);
+}
@@ -0,0 +1,128 @@
+#![cfg(not(windows))]
+
+use std::time::Duration;
+
+use gest::go::GoTestAction;
+use gest::runner::{
+    start_runner, PackageRun, RunKind, RunSpec, RunnerCommand, RunnerConfig, RunnerEvent,
+};
+
+#[test]
+fn retries_hide_failures_that_pass_later() {
+    let marker = std::env::temp_dir().join(format!("gest-retry-{}", std::process::id()));
+    let _ = std::fs::remove_file(&marker);
+    let fail = r#"{"Action":"fail","Package":"example","Test":"TestFlaky"}"#;
+    let pass = r#"{"Action":"pass","Package":"example","Test":"TestFlaky"}"#;
+    let script = format!(
+        "if [ -e '{marker}' ]; then echo '{pass}'; else touch '{marker}'; echo '{fail}'; exit 1; fi",
+        marker = marker.display(),
+    );
+    let (event_tx, event_rx) = crossbeam_channel::unbounded();
+    let runner_tx = start_runner(
+        RunnerConfig {
+            root: std::path::PathBuf::from("."),
+            pkg_concurrency: 1,
+            go_test_p: 1,
+            retries: 2,
+            test_command: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                script,
+                "sh".to_string(),
+            ]),
+            ..Default::default()
+        },
+        event_tx,
+    );
+    let _ = runner_tx.send(RunnerCommand::Run(RunSpec {
+        kind: RunKind::Single,
+        packages: vec![PackageRun {
+            packages: vec!["example".to_string()],
+            tests: Some(vec!["TestFlaky".to_string()]),
+        }],
+        no_test_cache_override: None,
+        timeout: None,
+        go_test_timeout: None,
+        run_pattern: None,
+    }));
+
+    let mut retries = Vec::new();
+    let mut actions = Vec::new();
+    let mut package_success = None;
+    while let Ok(event) = event_rx.recv_timeout(Duration::from_secs(5)) {
+        match event {
+            RunnerEvent::Retry { attempt, max, .. } => retries.push((attempt, max)),
+            RunnerEvent::TestEvent { event, .. } => actions.push(event.action),
+            RunnerEvent::PackageFinished { success, .. } => package_success = Some(success),
+            RunnerEvent::RunFinished { .. } => break,
+            _ => {}
+        }
+    }
+    let _ = runner_tx.send(RunnerCommand::Shutdown);
+    let _ = std::fs::remove_file(&marker);
+
+    assert_eq!(retries, vec![(2, 3)]);
+    assert_eq!(actions, vec![GoTestAction::Pass]);
+    assert_eq!(package_success, Some(true));
+}
+
+#[test]
+fn retries_only_reruns_and_holds_failfast_until_the_last_attempt() {
+    let fail = r#"{"Action":"fail","Package":"example","Test":"TestFlaky"}"#;
+    let pass = r#"{"Action":"pass","Package":"example","Test":"TestFlaky"}"#;
+    for (kind, expect_retry) in [(RunKind::Selected, true), (RunKind::Failing, false)] {
+        let marker =
+            std::env::temp_dir().join(format!("gest-retry-{:?}-{}", kind, std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+        let script = format!(
+            "if [ -e '{marker}' ]; then echo '{pass}'; else touch '{marker}'; echo '{fail}'; exit 1; fi",
+            marker = marker.display(),
+        );
+        let (event_tx, event_rx) = crossbeam_channel::unbounded();
+        let runner_tx = start_runner(
+            RunnerConfig {
+                root: std::path::PathBuf::from("."),
+                pkg_concurrency: 1,
+                go_test_p: 1,
+                retries: 2,
+                failfast: true,
+                test_command: Some(vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    script,
+                    "sh".to_string(),
+                ]),
+                ..Default::default()
+            },
+            event_tx,
+        );
+        let _ = runner_tx.send(RunnerCommand::Run(RunSpec {
+            kind,
+            packages: vec![PackageRun {
+                packages: vec!["example".to_string()],
+                tests: Some(vec!["TestFlaky".to_string()]),
+            }],
+            no_test_cache_override: None,
+            timeout: None,
+            go_test_timeout: None,
+            run_pattern: None,
+        }));
+
+        let mut retried = false;
+        let mut failed_fast = false;
+        while let Ok(event) = event_rx.recv_timeout(Duration::from_secs(5)) {
+            match event {
+                RunnerEvent::Retry { .. } => retried = true,
+                RunnerEvent::FailFast { .. } => failed_fast = true,
+                RunnerEvent::RunFinished { .. } => break,
+                _ => {}
+            }
+        }
+        let _ = runner_tx.send(RunnerCommand::Shutdown);
+        let _ = std::fs::remove_file(&marker);
+
+        assert_eq!(retried, expect_retry, "{:?}", kind);
+        // Only a failure that is not retried stops the run.
+        assert_eq!(failed_fast, !expect_retry, "{:?}", kind);
+    }
+}